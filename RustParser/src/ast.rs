@@ -0,0 +1,134 @@
+use serde::Serialize;
+
+use crate::TokenEdition;
+
+// The parsed program, filled in by the syntax analyzer alongside the Scheme and Prolog builders
+#[derive(Debug, Default, Serialize)]
+pub struct Program {
+    pub data: Vec<DataDef>,
+    pub inputs: Vec<InputOp>,
+    pub processes: Vec<ProcessOp>,
+    pub outputs: Vec<OutputOp>,
+    pub missing: Option<MissingPolicy>, // Program wide policy, a read can still give its own
+}
+
+// A single statement read on its own, as the REPL does, from any of the program sections
+#[derive(Debug, Clone, Serialize)]
+pub enum Statement {
+    Missing(MissingPolicy), // missing = drop
+    Data(DataDef),
+    Input(InputOp),
+    Process(ProcessOp),
+    Output(OutputOp),
+}
+
+// `xvalues : vector`
+#[derive(Debug, Clone, Serialize)]
+pub struct DataDef {
+    pub id: String,
+    pub kind: TokenEdition, // Either NUMBER or VECTOR
+}
+
+// Optional named arguments of read(...), e.g. read("f.tsv", true, 0, delimiter = "\t", quote = "'")
+// String values are kept as they appeared in the source, without the surrounding quotes
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ReadOptions {
+    pub delimiter: Option<String>,
+    pub quote: Option<String>,
+    pub encoding: Option<String>,
+    pub rows: Option<RowRange>,
+    pub skip_missing: bool,
+    pub missing: Option<MissingPolicy>,
+}
+
+// What happens to a blank, NA or null cell -> missing = drop | zero | error
+// Without a policy a missing cell is an error
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub enum MissingPolicy {
+    Drop,  // Kept as a gap, statistics leave it out and pairwise functions drop the whole pair
+    Zero,  // Read as 0
+    Error, // Stops the run
+}
+
+impl MissingPolicy {
+    pub fn name(&self) -> &'static str {
+        match self {
+            MissingPolicy::Drop => "drop",
+            MissingPolicy::Zero => "zero",
+            MissingPolicy::Error => "error",
+        }
+    }
+}
+
+// rows = 10..500 keeps the data rows 10 up to but not including 500, counted from 0 after any header
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct RowRange {
+    pub start: usize,
+    pub end: Option<usize>, // None for an open range -> rows = 10..
+}
+
+impl RowRange {
+    pub fn contains(&self, row: usize) -> bool {
+        row >= self.start && self.end.is_none_or(|end| row < end)
+    }
+}
+
+impl ReadOptions {
+    pub fn is_empty(&self) -> bool {
+        self.delimiter.is_none()
+            && self.quote.is_none()
+            && self.encoding.is_none()
+            && self.rows.is_none()
+            && !self.skip_missing
+            && self.missing.is_none()
+    }
+
+    // The string options that were given, in a fixed order, as (name, value) pairs
+    pub fn entries(&self) -> Vec<(&str, &str)> {
+        let mut entries = Vec::new();
+        if let Some(delimiter) = &self.delimiter {
+            entries.push(("delimiter", delimiter.as_str()));
+        }
+        if let Some(quote) = &self.quote {
+            entries.push(("quote", quote.as_str()));
+        }
+        if let Some(encoding) = &self.encoding {
+            entries.push(("encoding", encoding.as_str()));
+        }
+        entries
+    }
+}
+
+// Where the values of an input op come from, one variant per reader keyword
+#[derive(Debug, Clone, Serialize)]
+pub enum InputSource {
+    Csv { header: bool, column: usize },  // read("file.csv", false, 0)
+    Tsv { header: bool, column: usize },  // read_tsv("file.tsv", true, 1)
+    Json { path: String },                // read_json("file.json", "$.items[*].price")
+}
+
+// `xvalues = read("file.csv", false, 0)`
+#[derive(Debug, Clone, Serialize)]
+pub struct InputOp {
+    pub id: String,
+    pub file: String, // File path without the surrounding quotes, resolved against the PathBase
+    pub name: String, // The file name as the program wrote it, which the sql table mapping is keyed by
+    pub source: InputSource,
+    pub options: ReadOptions,
+}
+
+// `a = regressiona(xvalues, yvalues)`
+#[derive(Debug, Clone, Serialize)]
+pub struct ProcessOp {
+    pub id: String,
+    pub function: TokenEdition,
+    pub args: Vec<String>,
+}
+
+// Either a string literal (without the quotes) or an ID to display, or a write of named values to a file
+#[derive(Debug, Clone, Serialize)]
+pub enum OutputOp {
+    Text(String),
+    Value(String),
+    Write { file: String, ids: Vec<String> }, // write("results.csv", a, b, r)
+}
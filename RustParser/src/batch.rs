@@ -0,0 +1,189 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+use da::codegen::sql::Tables;
+use da::paths::PathBase;
+
+use crate::cli;
+
+// More than one input, a directory or a glob pattern means every file is built next to its input
+pub fn is_batch(inputs: &[String]) -> bool {
+    inputs.len() > 1 || inputs.iter().any(|input| Path::new(input).is_dir() || is_pattern(input))
+}
+
+fn is_pattern(input: &str) -> bool {
+    input.contains(['*', '?', '['])
+}
+
+// Builds every DA file the inputs name on all cores, input.da -> input.scm, and prints a summary
+pub fn build_all(
+    inputs: &[String],
+    target: &str,
+    standalone: bool,
+    paths: &PathBase,
+    tables: Option<&str>,
+    verbose: bool,
+) -> i32 {
+    let files = match expand_inputs(inputs) {
+        Ok(files) => files,
+        Err(message) => {
+            eprintln!("error: {}", message);
+            return cli::EXIT_USAGE;
+        }
+    };
+    if files.is_empty() {
+        eprintln!("error: no .da files found in {}", inputs.join(" "));
+        return cli::EXIT_USAGE;
+    }
+    let Some(extension) = da::extension(target) else {
+        eprintln!("error: {} is not a build target", target);
+        return cli::EXIT_USAGE;
+    };
+    // One table mapping serves every file
+    let tables = match tables.map(Tables::load).transpose() {
+        Ok(tables) => tables.unwrap_or_default(),
+        Err(message) => {
+            eprintln!("error: {}", message);
+            return cli::EXIT_DIAGNOSTICS;
+        }
+    };
+
+    // Each worker takes the next file that nobody has started yet
+    let next = AtomicUsize::new(0);
+    let codes = Mutex::new(vec![cli::EXIT_OK; files.len()]);
+    let workers = thread::available_parallelism().map_or(1, |n| n.get()).min(files.len());
+
+    thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| loop {
+                let i = next.fetch_add(1, Ordering::SeqCst);
+                if i >= files.len() {
+                    break;
+                }
+                let out = files[i].with_extension(extension);
+                let code = crate::compile(&files[i].to_string_lossy(), verbose, paths.clone(), |store| {
+                    store.set_tables(tables.clone());
+                    store.lex_and_parse_source()?;
+                    let code = if standalone { store.generate_standalone(target)? } else { store.generate(target)? };
+                    fs::write(&out, code).map_err(|e| format!("Could not write {}: {}", out.display(), e))?;
+                    Ok(())
+                });
+                codes.lock().unwrap()[i] = code;
+            });
+        }
+    });
+
+    // The report follows the order of the inputs, not the order the workers finished in
+    let codes = codes.into_inner().unwrap();
+    let mut failed = 0;
+    for (file, code) in files.iter().zip(&codes) {
+        if *code == cli::EXIT_OK {
+            let out = file.with_extension(extension);
+            println!("ok      {} -> {}", file.display(), out.display());
+        } else {
+            println!("failed  {}", file.display());
+            failed += 1;
+        }
+    }
+    println!("{} files: {} passed, {} failed", files.len(), files.len() - failed, failed);
+
+    if failed == 0 {
+        cli::EXIT_OK
+    } else {
+        cli::EXIT_DIAGNOSTICS
+    }
+}
+
+// Rewrites every DA file the inputs name in the canonical layout, with check it only lists the files that differ
+pub fn format_all(inputs: &[String], check: bool, verbose: bool) -> i32 {
+    let files = match expand_inputs(inputs) {
+        Ok(files) => files,
+        Err(message) => {
+            eprintln!("error: {}", message);
+            return cli::EXIT_USAGE;
+        }
+    };
+    if files.is_empty() {
+        eprintln!("error: no .da files found in {}", inputs.join(" "));
+        return cli::EXIT_USAGE;
+    }
+
+    let mut unformatted = 0;
+    let mut failed = 0;
+    for file in &files {
+        let mut changed = false;
+        // The file names are reprinted as written
+        let code = crate::compile(&file.to_string_lossy(), verbose, PathBase::CurrentDir, |store| {
+            store.lex_and_parse_source()?;
+            let formatted = da::format::format_program(store.program());
+            changed = formatted != store.source_code();
+            if changed && !check {
+                fs::write(file, formatted).map_err(|e| format!("Could not write {}: {}", file.display(), e))?;
+            }
+            Ok(())
+        });
+        if code != cli::EXIT_OK {
+            failed += 1;
+        } else if changed {
+            unformatted += 1;
+            if check {
+                println!("unformatted  {}", file.display());
+            } else if verbose {
+                eprintln!("Formatted {}", file.display());
+            }
+        }
+    }
+
+    if failed > 0 || (check && unformatted > 0) {
+        cli::EXIT_DIAGNOSTICS
+    } else {
+        cli::EXIT_OK
+    }
+}
+
+// Turns files, directories and glob patterns into the list of DA files to build
+fn expand_inputs(inputs: &[String]) -> Result<Vec<PathBuf>, String> {
+    let mut files = Vec::new();
+    for input in inputs {
+        if input == "-" {
+            return Err(String::from("stdin cannot be used together with other files"));
+        }
+        let path = Path::new(input);
+        if path.is_dir() {
+            collect_da_files(path, &mut files)?;
+        } else if is_pattern(input) {
+            let entries = glob::glob(input).map_err(|e| format!("bad pattern {}: {}", input, e))?;
+            for entry in entries {
+                let entry = entry.map_err(|e| e.to_string())?;
+                if entry.is_dir() {
+                    collect_da_files(&entry, &mut files)?;
+                } else {
+                    files.push(entry);
+                }
+            }
+        } else {
+            // A file that does not exist is reported as a failure when it is built
+            files.push(path.to_path_buf());
+        }
+    }
+    files.dedup();
+    Ok(files)
+}
+
+// Every .da file below a directory, in name order
+fn collect_da_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<(), String> {
+    let entries = fs::read_dir(dir).map_err(|e| format!("could not read {}: {}", dir.display(), e))?;
+    let mut paths: Vec<PathBuf> = entries.filter_map(|entry| entry.ok().map(|entry| entry.path())).collect();
+    paths.sort();
+    for path in paths {
+        if path.is_dir() {
+            collect_da_files(&path, files)?;
+        } else if path.extension().is_some_and(|ext| ext == "da") {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
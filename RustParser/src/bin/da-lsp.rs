@@ -0,0 +1,359 @@
+// Language server for DA files, speaks LSP over stdin and stdout
+//
+// Every open document is scanned and parsed again on each change. The tokens are kept even when the
+// program has an error, so hover and go to definition keep working on the part before it.
+
+use std::collections::HashMap;
+use std::error::Error;
+
+use lsp_server::{Connection, ErrorCode, Message, Notification, Request, Response};
+use lsp_types::notification::{
+    DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument, Notification as _, PublishDiagnostics,
+};
+use lsp_types::request::{Completion, GotoDefinition, HoverRequest, Request as _};
+use lsp_types::{
+    CompletionItem, CompletionItemKind, CompletionOptions, CompletionParams, CompletionResponse,
+    DiagnosticSeverity, DidChangeTextDocumentParams, DidCloseTextDocumentParams, DidOpenTextDocumentParams,
+    GotoDefinitionParams, GotoDefinitionResponse, Hover, HoverContents, HoverParams, HoverProviderCapability,
+    Location, MarkupContent, MarkupKind, OneOf, PublishDiagnosticsParams, Range, ServerCapabilities,
+    TextDocumentSyncCapability, TextDocumentSyncKind, Url,
+};
+
+use da::{Diagnostic, Token, TokenEdition};
+
+type Fallible<T> = Result<T, Box<dyn Error + Send + Sync>>;
+
+// The keywords offered by completion, with the way they are called
+const FUNCTIONS: [(&str, &str); 9] = [
+    ("regressiona", "regressiona(vector, vector) -> number, the intercept"),
+    ("regressionb", "regressionb(vector, vector) -> number, the slope"),
+    ("mean", "mean(vector) -> number"),
+    ("stddev", "stddev(vector) -> number, the sample standard deviation"),
+    ("correlation", "correlation(vector, vector) -> number"),
+    ("read", "read(\"file.csv\", header, column, options...) -> vector"),
+    ("read_tsv", "read_tsv(\"file.tsv\", header, column, options...) -> vector"),
+    ("read_json", "read_json(\"file.json\", \"$.path\", options...) -> vector"),
+    ("write", "write(\"results.csv\", id, ...)"),
+];
+
+// What is known about one open file
+struct Document {
+    tokens: Vec<Token>,
+    error: Option<Diagnostic>,
+}
+
+fn main() -> Fallible<()> {
+    let (connection, io_threads) = Connection::stdio();
+    let capabilities = serde_json::to_value(ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        definition_provider: Some(OneOf::Left(true)),
+        completion_provider: Some(CompletionOptions::default()),
+        ..Default::default()
+    })?;
+    connection.initialize(capabilities)?;
+    // The connection has to be dropped before the IO threads can finish
+    serve(connection)?;
+    io_threads.join()?;
+    Ok(())
+}
+
+fn serve(connection: Connection) -> Fallible<()> {
+    let mut documents: HashMap<Url, Document> = HashMap::new();
+
+    for message in &connection.receiver {
+        match message {
+            Message::Request(request) => {
+                if connection.handle_shutdown(&request)? {
+                    return Ok(());
+                }
+                let response = respond(&documents, request);
+                connection.sender.send(Message::Response(response))?;
+            }
+            Message::Notification(notification) => {
+                let method = notification.method.clone();
+                let changed = match update(&mut documents, notification) {
+                    Ok(changed) => changed,
+                    // A notification that cannot be read is skipped, the next change sends the whole text again
+                    Err(e) => {
+                        eprintln!("da-lsp: skipped {}: {}", method, e);
+                        None
+                    }
+                };
+                if let Some(uri) = changed {
+                    let diagnostics = match documents.get(&uri) {
+                        Some(document) => diagnostics(document),
+                        None => Vec::new(), // Closed, so its diagnostics are cleared
+                    };
+                    let params = PublishDiagnosticsParams { uri, diagnostics, version: None };
+                    let notification = Notification::new(PublishDiagnostics::METHOD.to_string(), params);
+                    connection.sender.send(Message::Notification(notification))?;
+                }
+            }
+            Message::Response(_) => {}
+        }
+    }
+    Ok(())
+}
+
+// Keeps the open documents in step with the editor, returns the file whose diagnostics changed
+fn update(documents: &mut HashMap<Url, Document>, notification: Notification) -> Fallible<Option<Url>> {
+    match notification.method.as_str() {
+        DidOpenTextDocument::METHOD => {
+            let params: DidOpenTextDocumentParams = serde_json::from_value(notification.params)?;
+            let uri = params.text_document.uri;
+            documents.insert(uri.clone(), analyze(&uri, &params.text_document.text));
+            Ok(Some(uri))
+        }
+        DidChangeTextDocument::METHOD => {
+            // The server asks for full sync, so the last change holds the whole text
+            let params: DidChangeTextDocumentParams = serde_json::from_value(notification.params)?;
+            let uri = params.text_document.uri;
+            match params.content_changes.last() {
+                Some(change) => {
+                    documents.insert(uri.clone(), analyze(&uri, &change.text));
+                    Ok(Some(uri))
+                }
+                None => Ok(None),
+            }
+        }
+        DidCloseTextDocument::METHOD => {
+            let params: DidCloseTextDocumentParams = serde_json::from_value(notification.params)?;
+            documents.remove(&params.text_document.uri);
+            Ok(Some(params.text_document.uri))
+        }
+        _ => Ok(None),
+    }
+}
+
+// The answer to a request, one whose params cannot be read gets an error and the server keeps running
+fn respond(documents: &HashMap<Url, Document>, request: Request) -> Response {
+    let id = request.id.clone();
+    answer(documents, request).unwrap_or_else(|e| Response::new_err(id, ErrorCode::InvalidParams as i32, e.to_string()))
+}
+
+fn answer(documents: &HashMap<Url, Document>, request: Request) -> Fallible<Response> {
+    let result = match request.method.as_str() {
+        HoverRequest::METHOD => {
+            let params: HoverParams = serde_json::from_value(request.params)?;
+            let at = params.text_document_position_params;
+            let hover = documents.get(&at.text_document.uri).and_then(|document| hover(document, at.position));
+            serde_json::to_value(hover)?
+        }
+        GotoDefinition::METHOD => {
+            let params: GotoDefinitionParams = serde_json::from_value(request.params)?;
+            let at = params.text_document_position_params;
+            let definition = documents.get(&at.text_document.uri).and_then(|document| {
+                let (id, _) = declaration(document, at.position)?;
+                let location = Location::new(at.text_document.uri.clone(), range(id));
+                Some(GotoDefinitionResponse::Scalar(location))
+            });
+            serde_json::to_value(definition)?
+        }
+        Completion::METHOD => {
+            let _: CompletionParams = serde_json::from_value(request.params)?;
+            let items = FUNCTIONS
+                .iter()
+                .map(|(name, detail)| CompletionItem {
+                    label: name.to_string(),
+                    kind: Some(CompletionItemKind::FUNCTION),
+                    detail: Some(detail.to_string()),
+                    ..Default::default()
+                })
+                .collect();
+            serde_json::to_value(CompletionResponse::Array(items))?
+        }
+        method => {
+            let message = format!("da-lsp does not handle {}", method);
+            return Ok(Response::new_err(request.id, ErrorCode::MethodNotFound as i32, message));
+        }
+    };
+    Ok(Response::new_ok(request.id, result))
+}
+
+fn analyze(uri: &Url, text: &str) -> Document {
+    let mut store = da::Store::new(text.to_string(), uri.to_string());
+    let error = store.lex_and_parse_source().err();
+    Document { tokens: store.tokens().to_vec(), error }
+}
+
+// The first error of the document, underlining the token it was found at
+fn diagnostics(document: &Document) -> Vec<lsp_types::Diagnostic> {
+    let Some(error) = &document.error else {
+        return Vec::new();
+    };
+    let position = error.position().unwrap_or_default();
+    let range = match document.tokens.iter().find(|token| token.position() == position) {
+        Some(token) => range(token),
+        None => {
+            let start = lsp_position(position);
+            Range::new(start, lsp_types::Position::new(start.line, start.character + 1))
+        }
+    };
+    let mut diagnostic = lsp_types::Diagnostic::new_simple(range, error.message().to_string());
+    diagnostic.severity = Some(DiagnosticSeverity::ERROR);
+    diagnostic.source = Some(String::from("da"));
+    vec![diagnostic]
+}
+
+// `xvalues : vector` for an identifier declared in the data section
+fn hover(document: &Document, position: lsp_types::Position) -> Option<Hover> {
+    let (id, kind) = declaration(document, position)?;
+    Some(Hover {
+        contents: HoverContents::Markup(MarkupContent {
+            kind: MarkupKind::Markdown,
+            value: format!("```da\n{} : {}\n```", id.literal(), kind.literal()),
+        }),
+        range: token_at(document, position).map(range),
+    })
+}
+
+// The ID and type tokens in the data section that declare the identifier under the cursor
+fn declaration(document: &Document, position: lsp_types::Position) -> Option<(&Token, &Token)> {
+    let token = token_at(document, position)?;
+    if token.kind() != TokenEdition::ID {
+        return None;
+    }
+    // Only the tokens before the input section can be declarations -> id : number, id : vector
+    let data_section = document
+        .tokens
+        .iter()
+        .position(|token| token.kind() == TokenEdition::INPUT)
+        .unwrap_or(document.tokens.len());
+    document.tokens[..data_section].windows(3).find_map(|window| {
+        let declares = window[0].kind() == TokenEdition::ID
+            && window[0].literal() == token.literal()
+            && window[1].kind() == TokenEdition::COLON
+            && matches!(window[2].kind(), TokenEdition::NUMBER | TokenEdition::VECTOR);
+        declares.then_some((&window[0], &window[2]))
+    })
+}
+
+fn token_at(document: &Document, position: lsp_types::Position) -> Option<&Token> {
+    document.tokens.iter().find(|token| {
+        let range = range(token);
+        range.start.line == position.line
+            && range.start.character <= position.character
+            && position.character < range.end.character
+    })
+}
+
+// The range a token covers, tokens never span lines
+fn range(token: &Token) -> Range {
+    let start = lsp_position(token.position());
+    let end = lsp_types::Position::new(start.line, start.character + token.literal().chars().count() as u32);
+    Range::new(start, end)
+}
+
+// LSP counts lines and characters from 0
+fn lsp_position(position: da::Position) -> lsp_types::Position {
+    lsp_types::Position::new(
+        position.line.saturating_sub(1) as u32,
+        position.column.saturating_sub(1) as u32,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lsp_server::RequestId;
+    use lsp_types::notification::Exit;
+    use lsp_types::request::Shutdown;
+    use serde_json::{json, Value};
+    use std::thread;
+
+    const URI: &str = "file:///prog.da";
+
+    // A server on one end of an in-memory connection, the test is the editor on the other
+    struct Editor {
+        client: Connection,
+        server: Option<thread::JoinHandle<()>>,
+        next_id: i32,
+    }
+
+    impl Editor {
+        fn start() -> Self {
+            let (server, client) = Connection::memory();
+            let server = thread::spawn(move || serve(server).unwrap());
+            Editor { client, server: Some(server), next_id: 0 }
+        }
+
+        fn notify(&self, method: &str, params: Value) {
+            let notification = Notification::new(method.to_string(), params);
+            self.client.sender.send(Message::Notification(notification)).unwrap();
+        }
+
+        fn request(&mut self, method: &str, params: Value) -> Response {
+            self.next_id += 1;
+            let id = RequestId::from(self.next_id);
+            self.client.sender.send(Message::Request(Request::new(id.clone(), method.to_string(), params))).unwrap();
+            match self.client.receiver.recv().unwrap() {
+                Message::Response(response) if response.id == id => response,
+                other => panic!("expected the response to {} but got {:?}", method, other),
+            }
+        }
+
+        fn open(&self, text: &str) -> PublishDiagnosticsParams {
+            let document = json!({ "uri": URI, "languageId": "da", "version": 1, "text": text });
+            self.notify(DidOpenTextDocument::METHOD, json!({ "textDocument": document }));
+            match self.client.receiver.recv().unwrap() {
+                Message::Notification(notification) if notification.method == PublishDiagnostics::METHOD => {
+                    serde_json::from_value(notification.params).unwrap()
+                }
+                other => panic!("expected diagnostics but got {:?}", other),
+            }
+        }
+
+        fn at(line: u32, character: u32) -> Value {
+            json!({ "textDocument": { "uri": URI }, "position": { "line": line, "character": character } })
+        }
+
+        fn shut_down(mut self) {
+            assert!(self.request(Shutdown::METHOD, Value::Null).error.is_none());
+            self.notify(Exit::METHOD, Value::Null);
+            self.server.take().unwrap().join().unwrap();
+        }
+    }
+
+    const PROGRAM: &str = "data:\n   x : vector,\n   m : number\ninput:\n   x = read(\"x.csv\", false, 0)\nprocess:\n   m = mean(x)\noutput:\n   m\nend.\n";
+
+    #[test]
+    fn diagnostics_of_an_open_document() {
+        let editor = Editor::start();
+        assert!(editor.open(PROGRAM).diagnostics.is_empty());
+        let published = editor.open("data:\n   x : vector\ninput:\n   x = read(\"x.csv\", false, 0)\n");
+        assert_eq!(published.diagnostics.len(), 1);
+        assert!(published.diagnostics[0].message.contains("end of file"), "{:?}", published.diagnostics);
+        editor.shut_down();
+    }
+
+    #[test]
+    fn hover_and_definition() {
+        let mut editor = Editor::start();
+        editor.open(PROGRAM);
+        // The m in m = mean(x) on line 7
+        let hover: Hover = serde_json::from_value(editor.request(HoverRequest::METHOD, Editor::at(6, 3)).result.unwrap()).unwrap();
+        let HoverContents::Markup(contents) = hover.contents else { panic!("not markup") };
+        assert!(contents.value.contains("m : number"), "{}", contents.value);
+        let response = editor.request(GotoDefinition::METHOD, Editor::at(6, 12));
+        let Some(GotoDefinitionResponse::Scalar(location)) = serde_json::from_value(response.result.unwrap()).unwrap() else {
+            panic!("no definition")
+        };
+        assert_eq!(location.range.start, lsp_types::Position::new(1, 3));
+        editor.shut_down();
+    }
+
+    #[test]
+    fn survives_a_malformed_request_and_notification() {
+        let mut editor = Editor::start();
+        editor.open(PROGRAM);
+        let error = editor.request(HoverRequest::METHOD, json!({ "position": "nowhere" })).error.unwrap();
+        assert_eq!(error.code, ErrorCode::InvalidParams as i32);
+        editor.notify(DidChangeTextDocument::METHOD, json!({ "contentChanges": 3 }));
+        let completion = editor.request(Completion::METHOD, Editor::at(6, 7));
+        assert!(completion.error.is_none());
+        let hover = editor.request(HoverRequest::METHOD, Editor::at(6, 3)).result.unwrap();
+        assert!(!hover.is_null(), "the server lost the document");
+        editor.shut_down();
+    }
+}
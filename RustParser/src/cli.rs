@@ -0,0 +1,368 @@
+// Command line handling for the da binary
+
+use std::path::PathBuf;
+
+use da::paths::PathBase;
+
+pub const EXIT_OK: i32 = 0;
+pub const EXIT_DIAGNOSTICS: i32 = 1; // A lexical, syntax or runtime error in the DA program
+pub const EXIT_USAGE: i32 = 2; // The command line itself was wrong
+
+
+pub const USAGE: &str = "Usage: da <command> [options] <file>
+
+A <file> of - reads the DA program from stdin.
+
+Commands:
+   check <file>                     Run lexical and syntax analysis only
+   build --target <target> <file>   Generate code for a target language
+   build --target <target> <path>...
+                                    Generate code for many files, directories or glob patterns
+                                    at once, each next to its input file
+   run <file>                       Run the program with the built-in interpreter
+   repl                             Read and run one statement at a time
+   fmt [--check] <path>...          Rewrite files, directories or glob patterns in the canonical
+                                    layout, a <file> of - prints the result to stdout
+
+Options:
+   -t, --target <target>   Language to generate: scheme, prolog, javascript, sql, julia, octave, rust
+   -o, --output <path>     Write the generated code to a file instead of stdout (build)
+   --standalone            Put the runtime in front of the generated code, so it runs on its own (build)
+   -v, --verbose           Print progress messages to stderr
+   -w, --watch             Run check, build or run again whenever the DA file or a file it reads changes
+   --emit <tokens|ast|dot> Print the tokens or the parsed program as JSON, or its dataflow as a
+                           Graphviz graph (check)
+   --relative-to <base>    Resolve the file names in read and write against cwd (the default) or source,
+                           the directory of the DA file (check, build, run)
+   --data-dir <dir>        Resolve the file names against <dir> and reject any that lead out of it
+                           (check, build, run)
+   --tables <file>         JSON mapping of CSV files to database tables (build --target sql)
+   --check                 Only list the files that are not formatted, exit 1 if there are any (fmt)
+   -h, --help              Print this message
+   -V, --version           Print the version
+
+The original form `da <file> -s|-p|-r` is still accepted for Scheme, Prolog and a local run.
+
+Exit codes: 0 success, 1 errors in the DA program, 2 wrong usage";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    Check { file: String, emit: Option<String>, paths: PathBase, verbose: bool },
+    Build {
+        files: Vec<String>,
+        target: String,
+        output: Option<String>,
+        standalone: bool,
+        paths: PathBase,
+        tables: Option<String>, // The table mapping file of the sql target
+        verbose: bool,
+    },
+    Run { file: String, paths: PathBase, verbose: bool },
+    Fmt { files: Vec<String>, check: bool, verbose: bool },
+    Watch(Box<Command>), // Check, build or run of a single file, repeated on every change
+    Repl,
+    Help,
+    Version,
+}
+
+// Turns the arguments after the program name into a command, flags may come in any order
+pub fn parse_args(args: &[String]) -> Result<Command, String> {
+    if args.iter().any(|arg| arg == "-h" || arg == "--help") {
+        return Ok(Command::Help);
+    }
+    if args.iter().any(|arg| arg == "-V" || arg == "--version") {
+        return Ok(Command::Version);
+    }
+    if args.is_empty() {
+        return Err(String::from("no command given"));
+    }
+
+    let rest = args;
+    let mut subcommand: Option<String> = None;
+    let mut files: Vec<String> = Vec::new();
+    let mut target = None;
+    let mut output = None;
+    let mut emit = None;
+    let mut relative_to = None;
+    let mut data_dir = None;
+    let mut tables = None;
+    let mut legacy = None; // -s, -p or -r from the original command line
+    let mut verbose = false;
+    let mut check = false;
+    let mut watch = false;
+    let mut standalone = false;
+
+    let mut i = 0;
+    while i < rest.len() {
+        let arg = rest[i].as_str();
+        // Options take their value either as the next argument or after an equals sign -> --target=scheme
+        let (name, inline_value) = match arg.split_once('=') {
+            Some((name, value)) if name.starts_with("--") => (name, Some(value.to_string())),
+            _ => (arg, None),
+        };
+
+        match name {
+            "-t" | "--target" | "-o" | "--output" | "--emit" | "--relative-to" | "--data-dir" | "--tables" => {
+                let value = match inline_value {
+                    Some(value) => value,
+                    None => {
+                        i += 1;
+                        match rest.get(i) {
+                            Some(value) => value.clone(),
+                            None => return Err(format!("{} needs a value", name)),
+                        }
+                    }
+                };
+                let slot = match name {
+                    "-t" | "--target" => &mut target,
+                    "-o" | "--output" => &mut output,
+                    "--relative-to" => &mut relative_to,
+                    "--data-dir" => &mut data_dir,
+                    "--tables" => &mut tables,
+                    _ => &mut emit,
+                };
+                if slot.is_some() {
+                    return Err(format!("{} was given more than once", name));
+                }
+                *slot = Some(value);
+            }
+            "-v" | "--verbose" => verbose = true,
+            "--check" => check = true,
+            "-w" | "--watch" => watch = true,
+            "--standalone" => standalone = true,
+            "-s" | "-p" | "-r" => {
+                if legacy.is_some() {
+                    return Err(String::from("only one of -s, -p and -r can be given"));
+                }
+                legacy = Some(name.to_string());
+            }
+            // A lone - is the file name for stdin
+            _ if name.starts_with('-') && name != "-" => return Err(format!("unknown option {}", arg)),
+            // The first plain argument names the command when it is one, flags may come before it
+            "check" | "build" | "run" | "fmt" | "repl" if subcommand.is_none() && files.is_empty() => {
+                subcommand = Some(name.to_string());
+            }
+            _ => files.push(arg.to_string()),
+        }
+        i += 1;
+    }
+
+    // The REPL reads its statements from the terminal and takes nothing else
+    if subcommand.as_deref() == Some("repl") {
+        if !files.is_empty() {
+            return Err(String::from("da repl does not take an input file"));
+        }
+        let valued = target.is_some()
+            || output.is_some()
+            || emit.is_some()
+            || relative_to.is_some()
+            || data_dir.is_some()
+            || tables.is_some();
+        if valued || legacy.is_some() || check || watch || standalone {
+            return Err(String::from("da repl does not take any options"));
+        }
+        return Ok(Command::Repl);
+    }
+
+    if files.is_empty() {
+        return Err(String::from("no input file given"));
+    }
+    if let Some(target) = &target {
+        if !da::TARGETS.contains(&target.as_str()) {
+            return Err(format!("unknown target {}, expected one of {}", target, da::TARGETS.join(", ")));
+        }
+    }
+    if let Some(emit) = &emit {
+        if emit != "tokens" && emit != "ast" && emit != "dot" {
+            return Err(format!("unknown --emit value {}, expected tokens, ast or dot", emit));
+        }
+    }
+
+    let paths = match (relative_to.as_deref(), &data_dir) {
+        (Some(_), Some(_)) => return Err(String::from("--relative-to and --data-dir cannot be used together")),
+        (None | Some("cwd"), None) => PathBase::CurrentDir,
+        (Some("source"), None) => PathBase::Source,
+        (Some(other), None) => return Err(format!("unknown --relative-to value {}, expected cwd or source", other)),
+        (None, Some(dir)) => PathBase::DataDir(PathBuf::from(dir)),
+    };
+
+    // The original command line maps onto the subcommands -> da file.da -s is da build --target scheme file.da
+    let subcommand = match (subcommand, legacy.as_deref()) {
+        (Some(subcommand), Some(flag)) => return Err(format!("{} cannot be used with da {}", flag, subcommand)),
+        (Some(subcommand), None) => subcommand,
+        (None, Some("-s")) => {
+            target.get_or_insert_with(|| String::from("scheme"));
+            String::from("build")
+        }
+        (None, Some("-p")) => {
+            target.get_or_insert_with(|| String::from("prolog"));
+            String::from("build")
+        }
+        (None, Some(_)) => String::from("run"),
+        (None, None) if emit.is_some() => String::from("check"),
+        (None, None) => return Err(format!("unknown command {}", args[0])),
+    };
+
+    let unused = |flag: &str, given: bool| -> Result<(), String> {
+        if given {
+            Err(format!("{} cannot be used with da {}", flag, subcommand))
+        } else {
+            Ok(())
+        }
+    };
+
+    // Only build and fmt take more than one input
+    if subcommand != "build" && subcommand != "fmt" && files.len() > 1 {
+        return Err(format!("da {} takes one input file, found {}", subcommand, files.len()));
+    }
+    let file = files[0].clone();
+
+    if subcommand != "fmt" {
+        unused("--check", check)?;
+    }
+    if subcommand != "build" {
+        unused("--standalone", standalone)?;
+        unused("--tables", tables.is_some())?;
+    }
+    if subcommand == "fmt" {
+        unused("--relative-to", relative_to.is_some())?;
+        unused("--data-dir", data_dir.is_some())?;
+    }
+    if watch {
+        if subcommand == "fmt" {
+            return Err(String::from("--watch cannot be used with da fmt"));
+        }
+        if files.len() > 1 || file == "-" {
+            return Err(String::from("--watch needs a single input file"));
+        }
+    }
+
+    let command = match subcommand.as_str() {
+        "check" => {
+            unused("--target", target.is_some())?;
+            unused("--output", output.is_some())?;
+            Command::Check { file, emit, paths, verbose }
+        }
+        "build" => {
+            unused("--emit", emit.is_some())?;
+            if files.len() > 1 && output.is_some() {
+                return Err(String::from("--output can only be used with a single input file"));
+            }
+            match target {
+                Some(target)
+                    if standalone
+                        && da::runtime::runtime(&target).is_none()
+                        && da::runtime::embedded(&target).is_none() =>
+                {
+                    return Err(format!("--standalone is not available for the {} target", target));
+                }
+                Some(target) if tables.is_some() && target != "sql" => {
+                    return Err(format!("--tables is only used by the sql target, not {}", target));
+                }
+                Some(target) => Command::Build { files, target, output, standalone, paths, tables, verbose },
+                None => return Err(String::from("da build needs a --target")),
+            }
+        }
+        "fmt" => {
+            unused("--target", target.is_some())?;
+            unused("--output", output.is_some())?;
+            unused("--emit", emit.is_some())?;
+            Command::Fmt { files, check, verbose }
+        }
+        _ => {
+            unused("--target", target.is_some())?;
+            unused("--output", output.is_some())?;
+            unused("--emit", emit.is_some())?;
+            Command::Run { file, paths, verbose }
+        }
+    };
+
+    if watch {
+        Ok(Command::Watch(Box::new(command)))
+    } else {
+        Ok(command)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(line: &str) -> Result<Command, String> {
+        let args: Vec<String> = line.split_whitespace().map(String::from).collect();
+        parse_args(&args)
+    }
+
+    #[test]
+    fn subcommands() {
+        assert_eq!(
+            parse("check prog.da").unwrap(),
+            Command::Check { file: "prog.da".into(), emit: None, paths: PathBase::CurrentDir, verbose: false }
+        );
+        assert_eq!(parse("run -v prog.da").unwrap(), Command::Run { file: "prog.da".into(), paths: PathBase::CurrentDir, verbose: true });
+        assert_eq!(parse("repl").unwrap(), Command::Repl);
+        assert_eq!(parse("fmt --check a.da b.da").unwrap(), Command::Fmt { files: vec!["a.da".into(), "b.da".into()], check: true, verbose: false });
+        assert_eq!(parse("--version").unwrap(), Command::Version);
+        assert_eq!(parse("build -h").unwrap(), Command::Help);
+    }
+
+    #[test]
+    fn options_take_their_value_either_way() {
+        let expected = Command::Build {
+            files: vec!["prog.da".into()],
+            target: "sql".into(),
+            output: Some("out.sql".into()),
+            standalone: false,
+            paths: PathBase::Source,
+            tables: Some("tables.json".into()),
+            verbose: false,
+        };
+        assert_eq!(parse("build --target sql -o out.sql --relative-to source --tables tables.json prog.da").unwrap(), expected);
+        assert_eq!(parse("--target=sql --output=out.sql build --relative-to=source --tables=tables.json prog.da").unwrap(), expected);
+    }
+
+    #[test]
+    fn the_original_flags_still_work() {
+        let Command::Build { target, .. } = parse("prog.da -s").unwrap() else { panic!("not a build") };
+        assert_eq!(target, "scheme");
+        let Command::Build { target, .. } = parse("prog.da -p").unwrap() else { panic!("not a build") };
+        assert_eq!(target, "prolog");
+        assert!(matches!(parse("prog.da -r").unwrap(), Command::Run { .. }));
+        assert!(parse("run prog.da -s").is_err());
+    }
+
+    #[test]
+    fn data_dir_and_watch() {
+        let Command::Run { paths, .. } = parse("run --data-dir data prog.da").unwrap() else { panic!("not a run") };
+        assert_eq!(paths, PathBase::DataDir(PathBuf::from("data")));
+        assert!(matches!(parse("check -w prog.da").unwrap(), Command::Watch(command) if matches!(*command, Command::Check { .. })));
+    }
+
+    #[test]
+    fn usage_errors() {
+        for line in [
+            "",
+            "check",
+            "build prog.da",
+            "build --target cobol prog.da",
+            "build --target scheme --target prolog prog.da",
+            "build --target scheme --tables t.json prog.da",
+            "build --target scheme -o out.scm a.da b.da",
+            "check --emit yaml prog.da",
+            "check --target scheme prog.da",
+            "run a.da b.da",
+            "run --relative-to source --data-dir data prog.da",
+            "run --relative-to home prog.da",
+            "fmt --data-dir data prog.da",
+            "fmt -w prog.da",
+            "check -w -",
+            "repl prog.da",
+            "repl --target scheme",
+            "check --bogus prog.da",
+            "build --target",
+            "prog.da",
+        ] {
+            assert!(parse(line).is_err(), "{:?} was accepted", line);
+        }
+    }
+}
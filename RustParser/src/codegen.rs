@@ -0,0 +1,7 @@
+// Backends that generate their code from the parsed program rather than while parsing
+
+pub mod javascript;
+pub mod julia;
+pub mod octave;
+pub mod rust;
+pub mod sql;
@@ -0,0 +1,99 @@
+use std::collections::HashSet;
+
+use crate::ast::{InputSource, OutputOp, Program, ReadOptions};
+use crate::format::function_name;
+use crate::interpreter::unescape;
+use crate::names::Names;
+use crate::quote::javascript_string;
+use crate::runtime;
+
+// An ES module with the runtime in front and the program as its main function, run when node is
+// started on the file -> node out.mjs, or imported and called by a bundle for the browser
+pub fn program(program: &Program, file_name: &str) -> String {
+    let mut names = Names::default();
+    let mut assigned = HashSet::new();
+    let mut body = Vec::new();
+
+    for input in &program.inputs {
+        // The program wide missing value policy goes to every read that has none of its own
+        let mut options = input.options.clone();
+        if options.missing.is_none() {
+            options.missing = program.missing;
+        }
+        let file = javascript_string(&input.file);
+        let call = match &input.source {
+            InputSource::Csv { header, column } => format!("readCsv({}, {}, {}", file, header, column),
+            InputSource::Tsv { header, column } => format!("readTsv({}, {}, {}", file, header, column),
+            InputSource::Json { path } => format!("readJson({}, {}", file, javascript_string(path)),
+        };
+        let call = if options.is_empty() {
+            format!("{})", call)
+        } else {
+            format!("{}, {})", call, read_options(&options))
+        };
+        body.push(assignment(&mut names, &mut assigned, &input.id, call));
+    }
+
+    for process in &program.processes {
+        let args: Vec<String> = process.args.iter().map(|arg| names.javascript_identifier(arg)).collect();
+        let call = format!("{}({})", function_name(process.function), args.join(", "));
+        body.push(assignment(&mut names, &mut assigned, &process.id, call));
+    }
+
+    for output in &program.outputs {
+        body.push(match output {
+            OutputOp::Text(text) => format!("io.log({});", javascript_string(&unescape(text))),
+            OutputOp::Value(id) => format!("io.log(show({}));", names.javascript_identifier(id)),
+            OutputOp::Write { file, ids } => {
+                let entries: Vec<String> = ids
+                    .iter()
+                    .map(|id| format!("{}: {}", javascript_string(id), names.javascript_identifier(id)))
+                    .collect();
+                format!("writeResults({}, {{ {} }});", javascript_string(file), entries.join(", "))
+            }
+        });
+    }
+
+    let mut code = format!("// Processing input file {}\n// Lexical and Syntax analysis passed\n\n", file_name);
+    code.push_str(runtime::embedded("javascript").unwrap());
+    code.push_str("\n// ---- Program ----\n\nexport function main() {\n");
+    for line in body {
+        code.push_str("  ");
+        code.push_str(&line);
+        code.push('\n');
+    }
+    code.push_str("}\n\nawait runAsScript(main);\n");
+    code
+}
+
+// An ID assigned again is not declared a second time -> let a = ...; then a = ...;
+fn assignment(names: &mut Names, assigned: &mut HashSet<String>, id: &str, value: String) -> String {
+    let name = names.javascript_identifier(id);
+    if assigned.insert(name.clone()) {
+        format!("let {} = {};", name, value)
+    } else {
+        format!("{} = {};", name, value)
+    }
+}
+
+// { delimiter: ";", rows: [10, 500], skipMissing: true, missing: "drop" }
+fn read_options(options: &ReadOptions) -> String {
+    let mut entries: Vec<String> = options
+        .entries()
+        .iter()
+        .map(|(name, value)| format!("{}: {}", name, javascript_string(&unescape(value))))
+        .collect();
+    if let Some(rows) = &options.rows {
+        match rows.end {
+            Some(end) => entries.push(format!("rows: [{}, {}]", rows.start, end)),
+            None => entries.push(format!("rows: [{}, null]", rows.start)),
+        }
+    }
+    if options.skip_missing {
+        entries.push("skipMissing: true".to_string());
+    }
+    if let Some(missing) = options.missing {
+        entries.push(format!("missing: {}", javascript_string(missing.name())));
+    }
+    format!("{{ {} }}", entries.join(", "))
+}
@@ -0,0 +1,99 @@
+use crate::ast::{InputOp, InputSource, MissingPolicy, OutputOp, ProcessOp, Program};
+use crate::interpreter::unescape;
+use crate::names::Names;
+use crate::quote::julia_string;
+use crate::runtime;
+use crate::TokenEdition;
+
+// A Julia script with the runtime in front, one line per op -> julia out.jl
+pub fn program(program: &Program, file_name: &str) -> Result<String, String> {
+    let mut names = Names::default();
+    let mut code = format!("# Processing input file {}\n# Lexical and Syntax analysis passed\n\n", file_name);
+    code.push_str(runtime::embedded("julia").unwrap());
+    code.push_str("\n# ---- Program ----\n\n");
+    for input in &program.inputs {
+        code.push_str(&input_op(input, program.missing, &mut names)?);
+        code.push('\n');
+    }
+    for process in &program.processes {
+        code.push_str(&process_op(process, &mut names)?);
+        code.push('\n');
+    }
+    for output in &program.outputs {
+        code.push_str(&output_op(output, &mut names));
+        code.push('\n');
+    }
+    Ok(code)
+}
+
+// x = read_column("file.csv", true, 0; delimiter=";", policy=:drop)
+fn input_op(input: &InputOp, missing: Option<MissingPolicy>, names: &mut Names) -> Result<String, String> {
+    let (reader, header, column) = match &input.source {
+        InputSource::Csv { header, column } => ("read_column", header, column),
+        InputSource::Tsv { header, column } => ("read_tsv_column", header, column),
+        InputSource::Json { .. } => {
+            return Err(format!("{} is read with read_json, which the julia target does not support", input.id))
+        }
+    };
+
+    let options = &input.options;
+    let mut keywords = Vec::new();
+    if let Some(delimiter) = &options.delimiter {
+        keywords.push(format!("delimiter={}", julia_string(&unescape(delimiter))));
+    }
+    if let Some(quote) = &options.quote {
+        keywords.push(format!("quote_char={}", julia_string(&unescape(quote))));
+    }
+    if let Some(encoding) = &options.encoding {
+        keywords.push(format!("encoding={}", julia_string(&unescape(encoding))));
+    }
+    if let Some(rows) = &options.rows {
+        match rows.end {
+            Some(end) => keywords.push(format!("rows=({}, {})", rows.start, end)),
+            None => keywords.push(format!("rows=({}, nothing)", rows.start)),
+        }
+    }
+    if options.skip_missing {
+        keywords.push("skip_missing=true".to_string());
+    }
+    // The program wide policy applies to a read that has none of its own
+    if let Some(policy) = options.missing.or(missing) {
+        keywords.push(format!("policy=:{}", policy.name()));
+    }
+
+    let mut call = format!("{}({}, {}, {}", reader, julia_string(&input.file), header, column);
+    if !keywords.is_empty() {
+        call.push_str("; ");
+        call.push_str(&keywords.join(", "));
+    }
+    Ok(format!("{} = {})", names.julia_identifier(&input.id), call))
+}
+
+// m = mean(present(x)), r = cor(complete_pairs(x, y)...)
+fn process_op(process: &ProcessOp, names: &mut Names) -> Result<String, String> {
+    let args: Vec<String> = process.args.iter().map(|arg| names.julia_identifier(arg)).collect();
+    let value = match process.function {
+        TokenEdition::MEAN => format!("mean(present({}))", args[0]),
+        TokenEdition::STDDEV => format!("std(present({}))", args[0]),
+        TokenEdition::CORRELATION => format!("cor(complete_pairs({}, {})...)", args[0], args[1]),
+        TokenEdition::REGRESSIONA => format!("intercept(complete_pairs({}, {})...)", args[0], args[1]),
+        TokenEdition::REGRESSIONB => format!("slope(complete_pairs({}, {})...)", args[0], args[1]),
+        other => return Err(format!("{:?} is not a process function", other)),
+    };
+    Ok(format!("{} = {}", names.julia_identifier(&process.id), value))
+}
+
+fn output_op(output: &OutputOp, names: &mut Names) -> String {
+    match output {
+        OutputOp::Text(text) => format!("println({})", julia_string(&unescape(text))),
+        OutputOp::Value(id) => format!("println({})", names.julia_identifier(id)),
+        // write_results("results.csv", ["m" => m, "x" => x])
+        OutputOp::Write { file, ids } => {
+            let pairs: Vec<String> = ids
+                .iter()
+                .map(|id| format!("{} => {}", julia_string(id), names.julia_identifier(id)))
+                .collect();
+            format!("write_results({}, [{}])", julia_string(file), pairs.join(", "))
+        }
+    }
+}
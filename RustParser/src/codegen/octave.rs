@@ -0,0 +1,183 @@
+use crate::ast::{InputOp, InputSource, MissingPolicy, OutputOp, ProcessOp, Program};
+use crate::interpreter::unescape;
+use crate::names::Names;
+use crate::quote::octave_string;
+use crate::TokenEdition;
+
+// An Octave script that also runs in MATLAB, one block per op -> octave out.m
+// csvread and dlmread only know blank cells as missing, so the script splits the lines itself
+pub fn program(program: &Program, file_name: &str) -> Result<String, String> {
+    let mut names = Names::default();
+    let mut code = format!("% Processing input file {}\n% Lexical and Syntax analysis passed\n\n", file_name);
+    for input in &program.inputs {
+        code.push_str(&input_op(input, program.missing, &mut names)?);
+        code.push('\n');
+    }
+    for process in &program.processes {
+        code.push_str(&process_op(process, &mut names)?);
+        code.push('\n');
+    }
+    for output in &program.outputs {
+        code.push_str(&output_op(output, &mut names)?);
+        code.push('\n');
+    }
+    Ok(code)
+}
+
+// A loop over the lines of the file that reads one cell of each as a number or NaN, followed by the
+// read options
+fn input_op(input: &InputOp, missing: Option<MissingPolicy>, names: &mut Names) -> Result<String, String> {
+    let (header, column) = match &input.source {
+        InputSource::Csv { header, column } | InputSource::Tsv { header, column } => (*header, *column),
+        InputSource::Json { .. } => {
+            return Err(format!("{} is read with read_json, which the octave target does not support", input.id))
+        }
+    };
+    let options = &input.options;
+    if options.quote.is_some() {
+        return Err(format!("{} sets a quote character, which the octave target does not support", input.id));
+    }
+    if let Some(encoding) = &options.encoding {
+        let encoding = unescape(encoding).to_lowercase();
+        if encoding != "utf-8" && encoding != "utf8" {
+            return Err(format!("{} is read as {}, the octave target only reads utf-8", input.id, encoding));
+        }
+    }
+
+    let name = names.octave_identifier(&input.id);
+    let file = octave_string(&input.file);
+    let delimiter = match (&input.source, &options.delimiter) {
+        (InputSource::Tsv { .. }, _) => octave_string("\t"),
+        (_, Some(delimiter)) => octave_string(&unescape(delimiter)),
+        _ => octave_string(","),
+    };
+    // Blank lines are left out before the header row is, the row in an error counts from 1 with the header
+    let first_line = if header { 2 } else { 1 };
+    let message = format!("%s row %d column {}: %s is not a number", column);
+    let mut lines = vec![
+        format!("da_lines = regexp(fileread({}), '\\r\\n|\\n|\\r', 'split');", file),
+        "da_lines = da_lines(~cellfun(@isempty, da_lines));".to_string(),
+        format!("da_lines = da_lines({}:end);", first_line),
+        format!("da_delimiter = regexptranslate('escape', {});", delimiter),
+        format!("{} = NaN(numel(da_lines), 1);", name),
+        "for da_row = 1:numel(da_lines)".to_string(),
+        "  da_cells = regexp(da_lines{da_row}, da_delimiter, 'split');".to_string(),
+        // A short row counts as a missing cell
+        "  da_cell = '';".to_string(),
+        format!("  if numel(da_cells) >= {}, da_cell = strtrim(da_cells{{{}}}); end", column + 1, column + 1),
+        "  if ~any(strcmpi(da_cell, {'', 'na', 'n/a', 'nan', 'null'}))".to_string(),
+        format!("    {}(da_row) = str2double(da_cell);", name),
+        format!(
+            "    if isnan({}(da_row)), error({}, {}, da_row + {}, da_cell); end",
+            name,
+            octave_string(&message),
+            file,
+            first_line - 1
+        ),
+        "  end".to_string(),
+        "end".to_string(),
+    ];
+    if let Some(rows) = &options.rows {
+        match rows.end {
+            Some(end) => lines.push(format!("{} = {}({}:min({}, end));", name, name, rows.start + 1, end)),
+            None => lines.push(format!("{} = {}({}:end);", name, name, rows.start + 1)),
+        }
+    }
+    // Missing cells are read as NaN, which is what missing = drop keeps
+    if options.skip_missing {
+        lines.push(format!("{} = {}(~isnan({}));", name, name, name));
+    } else {
+        match options.missing.or(missing) {
+            Some(MissingPolicy::Drop) => {}
+            Some(MissingPolicy::Zero) => lines.push(format!("{}(isnan({})) = 0;", name, name)),
+            Some(MissingPolicy::Error) | None => {
+                let message = format!(
+                    "{} column {}: a value is missing, use missing = drop or missing = zero to allow missing values",
+                    input.file, column
+                );
+                lines.push(format!("if any(isnan({})), error({}); end", name, octave_string(&message)));
+            }
+        }
+    }
+    Ok(lines.join("\n"))
+}
+
+// m = mean(x(~isnan(x))); pairwise functions first keep the pairs where both values are present
+fn process_op(process: &ProcessOp, names: &mut Names) -> Result<String, String> {
+    let args: Vec<String> = process.args.iter().map(|arg| names.octave_identifier(arg)).collect();
+    let name = names.octave_identifier(&process.id);
+    let keep = || format!("da_keep = ~isnan({}) & ~isnan({});", args[0], args[1]);
+    // polyfit gives the slope b first and the intercept a second
+    let fit = || format!("da_fit = polyfit({}(da_keep), {}(da_keep), 1);", args[0], args[1]);
+    let code = match process.function {
+        TokenEdition::MEAN => format!("{} = mean({}(~isnan({})));", name, args[0], args[0]),
+        TokenEdition::STDDEV => format!("{} = std({}(~isnan({})));", name, args[0], args[0]),
+        // corr needs the Statistics Toolbox in MATLAB, corrcoef does not
+        TokenEdition::CORRELATION => format!(
+            "{}\nda_r = corrcoef({}(da_keep), {}(da_keep));\n{} = da_r(1, 2);",
+            keep(),
+            args[0],
+            args[1],
+            name
+        ),
+        TokenEdition::REGRESSIONA => format!("{}\n{}\n{} = da_fit(2);", keep(), fit(), name),
+        TokenEdition::REGRESSIONB => format!("{}\n{}\n{} = da_fit(1);", keep(), fit(), name),
+        other => return Err(format!("{:?} is not a process function", other)),
+    };
+    Ok(code)
+}
+
+fn output_op(output: &OutputOp, names: &mut Names) -> Result<String, String> {
+    match output {
+        OutputOp::Text(text) => Ok(format!("disp({});", octave_string(&unescape(text)))),
+        OutputOp::Value(id) => Ok(format!("disp({});", names.octave_identifier(id))),
+        OutputOp::Write { file, ids } => {
+            let values: Vec<String> = ids.iter().map(|id| names.octave_identifier(id)).collect();
+            if file.to_lowercase().ends_with(".json") {
+                write_json(file, ids, &values)
+            } else {
+                Ok(write_csv(file, ids, &values))
+            }
+        }
+    }
+}
+
+// fprintf(da_file, '%s\n', jsonencode(struct('m', m, 'x', x))), NaN is written as null
+fn write_json(file: &str, ids: &[String], values: &[String]) -> Result<String, String> {
+    let mut fields = Vec::new();
+    for (id, value) in ids.iter().zip(values) {
+        if !id.starts_with(|c: char| c.is_ascii_alphabetic()) {
+            return Err(format!("{} cannot be a field of an octave struct, which has to start with a letter", id));
+        }
+        fields.push(format!("'{}', {}", id, value));
+    }
+    Ok(format!(
+        "da_file = fopen({}, 'w');\nfprintf(da_file, '%s\\n', jsonencode(struct({})));\nfclose(da_file);",
+        octave_string(file),
+        fields.join(", ")
+    ))
+}
+
+// The same layout as the other targets, a number only fills the first row and a vector as many
+// rows as it has values, with NaN left blank
+fn write_csv(file: &str, ids: &[String], values: &[String]) -> String {
+    [
+        format!("da_values = {{{}}};", values.join(", ")),
+        format!("da_file = fopen({}, 'w');", octave_string(file)),
+        format!("fprintf(da_file, '%s\\n', {});", octave_string(&ids.join(","))),
+        "for da_row = 1:max(cellfun(@numel, da_values))".to_string(),
+        "  da_cells = cell(1, numel(da_values));".to_string(),
+        "  for da_col = 1:numel(da_values)".to_string(),
+        "    da_value = da_values{da_col};".to_string(),
+        "    if da_row <= numel(da_value) && ~isnan(da_value(da_row))".to_string(),
+        "      da_cells{da_col} = sprintf('%.15g', da_value(da_row));".to_string(),
+        "    else".to_string(),
+        "      da_cells{da_col} = '';".to_string(),
+        "    end".to_string(),
+        "  end".to_string(),
+        "  fprintf(da_file, '%s\\n', strjoin(da_cells, ','));".to_string(),
+        "end".to_string(),
+        "fclose(da_file);".to_string(),
+    ]
+    .join("\n")
+}
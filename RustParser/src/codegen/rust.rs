@@ -0,0 +1,110 @@
+use crate::ast::{InputSource, MissingPolicy, OutputOp, Program, ReadOptions};
+use crate::format::function_name;
+use crate::interpreter::unescape;
+use crate::names::Names;
+use crate::quote::rust_string;
+use crate::runtime;
+
+// A main.rs with the runtime in front and the program as its run function, which needs nothing but
+// the standard library -> rustc -O out.rs, or src/main.rs of the crate written by da build -o <dir>
+pub fn program(program: &Program, file_name: &str) -> String {
+    let mut names = Names::default();
+    let mut body = Vec::new();
+
+    for input in &program.inputs {
+        // The program wide missing value policy goes to every read that has none of its own
+        let mut options = input.options.clone();
+        if options.missing.is_none() {
+            options.missing = program.missing;
+        }
+        let file = rust_string(&input.file);
+        let call = match &input.source {
+            InputSource::Csv { header, column } => format!("read_csv({}, {}, {}", file, header, column),
+            InputSource::Tsv { header, column } => format!("read_tsv({}, {}, {}", file, header, column),
+            InputSource::Json { path } => format!("read_json({}, {}", file, rust_string(path)),
+        };
+        body.push(format!("let {} = {}, &{})?;", names.rust_identifier(&input.id), call, read_options(&options)));
+    }
+
+    // An ID assigned again is a new let that shadows the old one
+    for process in &program.processes {
+        let args: Vec<String> = process.args.iter().map(|arg| format!("&{}", names.rust_identifier(arg))).collect();
+        let call = format!("{}({})", function_name(process.function), args.join(", "));
+        body.push(format!("let {} = {}?;", names.rust_identifier(&process.id), call));
+    }
+
+    for output in &program.outputs {
+        body.push(match output {
+            // The text is the format string itself, with its braces doubled
+            OutputOp::Text(text) => {
+                let text = unescape(text).replace('{', "{{").replace('}', "}}");
+                format!("println!({});", rust_string(&text))
+            }
+            OutputOp::Value(id) => format!("println!(\"{{}}\", {}.show());", names.rust_identifier(id)),
+            OutputOp::Write { file, ids } => {
+                let entries: Vec<String> = ids
+                    .iter()
+                    .map(|id| format!("({}, &{} as &dyn Value)", rust_string(id), names.rust_identifier(id)))
+                    .collect();
+                format!("write_results({}, &[{}])?;", rust_string(file), entries.join(", "))
+            }
+        });
+    }
+
+    let mut code = format!("// Processing input file {}\n// Lexical and Syntax analysis passed\n\n", file_name);
+    code.push_str(runtime::embedded("rust").unwrap());
+    code.push_str("\n// ---- Program ----\n\nfn run() -> Result<(), String> {\n");
+    for line in body {
+        code.push_str("    ");
+        code.push_str(&line);
+        code.push('\n');
+    }
+    code.push_str("    Ok(())\n}\n");
+    code
+}
+
+// The Cargo.toml of a crate around the generated main.rs, named after its directory
+pub fn manifest(name: &str) -> String {
+    let mut package: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '_' || c == '-' { c.to_ascii_lowercase() } else { '-' })
+        .collect();
+    // A package name has to start with a letter
+    if !package.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        package = format!("da-{}", package);
+    }
+    format!(
+        "[package]\nname = \"{}\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n[dependencies]\n",
+        package
+    )
+}
+
+// ReadOptions { delimiter: Some(";"), rows: Some((10, Some(500))), ..ReadOptions::default() }
+fn read_options(options: &ReadOptions) -> String {
+    if options.is_empty() {
+        return "ReadOptions::default()".to_string();
+    }
+    let mut fields: Vec<String> = options
+        .entries()
+        .iter()
+        .map(|(name, value)| format!("{}: Some({})", name, rust_string(&unescape(value))))
+        .collect();
+    if let Some(rows) = &options.rows {
+        match rows.end {
+            Some(end) => fields.push(format!("rows: Some(({}, Some({})))", rows.start, end)),
+            None => fields.push(format!("rows: Some(({}, None))", rows.start)),
+        }
+    }
+    if options.skip_missing {
+        fields.push("skip_missing: true".to_string());
+    }
+    if let Some(missing) = options.missing {
+        let variant = match missing {
+            MissingPolicy::Drop => "Drop",
+            MissingPolicy::Zero => "Zero",
+            MissingPolicy::Error => "Error",
+        };
+        fields.push(format!("missing: Some(Missing::{})", variant));
+    }
+    format!("ReadOptions {{ {}, ..ReadOptions::default() }}", fields.join(", "))
+}
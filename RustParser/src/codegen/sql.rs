@@ -0,0 +1,311 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::ast::{InputOp, InputSource, MissingPolicy, Program, ProcessOp};
+use crate::interpreter;
+use crate::quote::sql_identifier;
+use crate::TokenEdition;
+
+// Which database table holds the data a CSV file was exported from, read from a JSON file and keyed
+// by the file names as the program writes them, whatever --relative-to or --data-dir makes of them:
+// { "data/sales.csv": "sales", "m.csv": { "table": "measurements", "columns": ["x", "y"] } }
+#[derive(Debug, Clone, Default)]
+pub struct Tables {
+    files: HashMap<String, Table>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+enum Table {
+    Name(String),
+    Columns { table: String, columns: Option<Vec<String>> },
+}
+
+impl Tables {
+    pub fn from_json(text: &str) -> Result<Self, String> {
+        let files = serde_json::from_str(text).map_err(|e| format!("The table mapping is not valid: {}", e))?;
+        Ok(Self { files })
+    }
+
+    pub fn load(path: &str) -> Result<Self, String> {
+        let text = fs::read_to_string(path).map_err(|e| format!("Could not read {}: {}", path, e))?;
+        Self::from_json(&text).map_err(|message| format!("{}: {}", path, message))
+    }
+
+    // The table and the column a read refers to -> ("sales", "price") for read("sales.csv", true, 2)
+    fn column(&self, input: &InputOp) -> Result<(String, String), String> {
+        let (header, column) = match &input.source {
+            InputSource::Csv { header, column } | InputSource::Tsv { header, column } => (*header, *column),
+            InputSource::Json { .. } => {
+                return Err(format!("{} is read with read_json, which the sql target does not support", input.id))
+            }
+        };
+        if input.options.rows.is_some() {
+            return Err(format!("The sql target has no row order, so {} cannot use rows", input.id));
+        }
+
+        // Without a mapping the table is named after the file -> data/sales.csv is sales
+        let (table, columns) = match self.files.get(&input.name) {
+            Some(Table::Name(table)) => (table.clone(), None),
+            Some(Table::Columns { table, columns }) => (table.clone(), columns.clone()),
+            None => {
+                let stem = Path::new(&input.name).file_stem().map(|stem| stem.to_string_lossy().into_owned());
+                (stem.unwrap_or_default(), None)
+            }
+        };
+        // Column names missing from the mapping come from the header row of the CSV file
+        let columns = match columns {
+            Some(columns) => columns,
+            None if header => {
+                let mut options = input.options.clone();
+                if matches!(input.source, InputSource::Tsv { .. }) {
+                    options.delimiter = Some("\\t".to_string());
+                }
+                interpreter::read_header(&input.file, &options)?
+            }
+            None => {
+                return Err(format!(
+                    "{} has no header row, so the table mapping has to give the column names of {}",
+                    input.file, table
+                ))
+            }
+        };
+        match columns.get(column) {
+            Some(name) => Ok((table, name.clone())),
+            None => Err(format!("{} has no column {}, it has {}", table, column, columns.len())),
+        }
+    }
+}
+
+// A vector as a column of a table, with what its missing values become
+struct Column {
+    table: String,
+    expression: String, // The values as floating point numbers, so that no aggregate divides integers
+    required: Option<String>, // The column, when a missing value in it is an error
+}
+
+impl Column {
+    fn of(input: &InputOp, tables: &Tables, program: &Program) -> Result<Self, String> {
+        let (table, column) = tables.column(input)?;
+        let name = format!("{}.{}", sql_identifier(&table), sql_identifier(&column));
+        let policy = if input.options.skip_missing { Some(MissingPolicy::Drop) } else { input.options.missing.or(program.missing) };
+        // Aggregates leave NULL out, which is what missing = drop does; zero has to be asked for
+        let (value, required) = match policy {
+            Some(MissingPolicy::Drop) => (name, None),
+            Some(MissingPolicy::Zero) => (format!("COALESCE({}, 0)", name), None),
+            Some(MissingPolicy::Error) | None => (name.clone(), Some(name)),
+        };
+        Ok(Self { table, expression: format!("CAST({} AS DOUBLE PRECISION)", value), required })
+    }
+}
+
+// A single SELECT that computes every number of the data section as a column named after it.
+// The file of each read becomes a table through the mapping and every process function an aggregate
+pub fn program(program: &Program, tables: &Tables, file_name: &str) -> Result<String, String> {
+    // The last process op that assigns a number gives its value
+    let mut selected = Vec::new();
+    for def in program.data.iter().filter(|def| def.kind == TokenEdition::NUMBER) {
+        let process = program.processes.iter().rev().find(|process| process.id == def.id);
+        match process {
+            Some(process) => selected.push((def.id.as_str(), aggregate(process, program, tables)?)),
+            None => {
+                return Err(format!(
+                    "{} is declared as a number but never computed, so there is nothing to select",
+                    def.id
+                ))
+            }
+        }
+    }
+    if selected.is_empty() {
+        return Err(String::from("The data section has no number for the sql target to select"));
+    }
+
+    let mut code = format!("-- Processing input file {}\n-- Lexical and Syntax analysis passed\n\n", file_name);
+    let mut used: Vec<&str> = selected.iter().map(|(_, (table, _))| table.as_str()).collect();
+    used.sort();
+    used.dedup();
+    if used.len() == 1 {
+        // Everything comes from one table -> SELECT AVG(x) AS m, ... FROM t;
+        let columns: Vec<String> = selected
+            .iter()
+            .map(|(id, (_, aggregate))| format!("   {} AS {}", aggregate, sql_identifier(id)))
+            .collect();
+        code.push_str(&format!("SELECT\n{}\nFROM {};\n", columns.join(",\n"), sql_identifier(used[0])));
+    } else {
+        // Each number is a scalar subquery over its own table
+        let columns: Vec<String> = selected
+            .iter()
+            .map(|(id, (table, aggregate))| {
+                format!("   (SELECT {} FROM {}) AS {}", aggregate, sql_identifier(table), sql_identifier(id))
+            })
+            .collect();
+        code.push_str(&format!("SELECT\n{};\n", columns.join(",\n")));
+    }
+    Ok(code)
+}
+
+// The table and the aggregate of a process op -> mean(x) is AVG(x)
+fn aggregate(process: &ProcessOp, program: &Program, tables: &Tables) -> Result<(String, String), String> {
+    // The last read of a vector before the process section is the one the process op sees
+    let args = process
+        .args
+        .iter()
+        .map(|arg| match program.inputs.iter().rev().find(|input| &input.id == arg) {
+            Some(input) => Column::of(input, tables, program),
+            None => Err(format!("{} is used by {} but is not read from a table", arg, process.id)),
+        })
+        .collect::<Result<Vec<Column>, String>>()?;
+    // Rows of two tables do not line up, so both vectors of a pairwise function need the same table
+    if args.len() == 2 && args[0].table != args[1].table {
+        return Err(format!(
+            "{} pairs {} and {}, which come from the different tables {} and {}",
+            process.id, process.args[0], process.args[1], args[0].table, args[1].table
+        ));
+    }
+    let aggregate = match (process.function, args.as_slice()) {
+        (TokenEdition::MEAN, [x]) => format!("AVG({})", x.expression),
+        (TokenEdition::STDDEV, [x]) => {
+            format!("SQRT({} / (COUNT({}) - 1))", deviations(&x.expression, &x.expression), x.expression)
+        }
+        (function, [x, y]) => {
+            // Pairwise functions only use the rows where both values are present
+            let x_pairs = format!("CASE WHEN {} IS NOT NULL THEN {} END", y.expression, x.expression);
+            let y_pairs = format!("CASE WHEN {} IS NOT NULL THEN {} END", x.expression, y.expression);
+            let sxx = deviations(&x_pairs, &x_pairs);
+            let sxy = deviations(&x_pairs, &y_pairs);
+            match function {
+                TokenEdition::CORRELATION => {
+                    format!("{} / SQRT({} * {})", sxy, sxx, deviations(&y_pairs, &y_pairs))
+                }
+                TokenEdition::REGRESSIONA => format!("AVG({}) - {} / {} * AVG({})", y_pairs, sxy, sxx, x_pairs),
+                TokenEdition::REGRESSIONB => format!("{} / {}", sxy, sxx),
+                other => return Err(format!("{:?} is not a pairwise function", other)),
+            }
+        }
+        (other, _) => return Err(format!("{:?} is not a process function", other)),
+    };
+
+    // A missing value that is an error leaves the number NULL instead of computing it without the value
+    let mut required: Vec<String> = args.iter().filter_map(|arg| arg.required.clone()).collect();
+    required.dedup();
+    if required.is_empty() {
+        return Ok((args[0].table.clone(), aggregate));
+    }
+    let complete: Vec<String> = required.iter().map(|column| format!("COUNT({}) = COUNT(*)", column)).collect();
+    Ok((args[0].table.clone(), format!("CASE WHEN {} THEN {} END", complete.join(" AND "), aggregate)))
+}
+
+// The sum of the products of the deviations from the mean, written with plain aggregates so that
+// SQLite runs it as well -> SUM(x * y) - SUM(x) * SUM(y) / COUNT(x)
+fn deviations(x: &str, y: &str) -> String {
+    format!("(SUM({0} * {1}) - SUM({0}) * SUM({1}) / COUNT({0}))", x, y)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interpreter;
+    use std::env;
+    use std::process::Command;
+
+    const CSV: &str = "x,y\n1,2.5\n2,3.9\n3,6.2\n4,NA\n5,9.7\n6,12.2\n";
+
+    // The query run by the sqlite3 shell on a table measurements(x, y) holding CSV,
+    // None when there is no sqlite3 to run it
+    fn sqlite(query: &str) -> Option<Vec<String>> {
+        let mut script = String::from("CREATE TABLE measurements (x INTEGER, y REAL);\n");
+        for line in CSV.lines().skip(1) {
+            let cells: Vec<&str> = line.split(',').map(|cell| if cell == "NA" { "NULL" } else { cell }).collect();
+            script.push_str(&format!("INSERT INTO measurements VALUES ({});\n", cells.join(", ")));
+        }
+        script.push_str(query);
+        let dir = env::temp_dir().join(format!("da-sql-{}-{}", std::process::id(), query.len()));
+        fs::write(&dir, script).unwrap();
+        let output = Command::new("sqlite3").arg(":memory:").arg(format!(".read {}", dir.display())).output();
+        fs::remove_file(&dir).unwrap();
+        let output = output.ok()?;
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        Some(stdout.trim_end().split('|').map(str::to_string).collect())
+    }
+
+    // The program reads m.csv through the table mapping, which is keyed by the name as written
+    fn query(missing: &str) -> (Program, String) {
+        let source = format!(
+            "{}data:\n x : vector,\n y : vector,\n mx : number,\n sy : number,\n r : number,\n a : number,\n b : number\n\
+             input:\n x = read(\"data/m.csv\", false, 0),\n y = read(\"data/m.csv\", false, 1)\n\
+             process:\n mx = mean(x),\n sy = stddev(y),\n r = correlation(x, y),\n a = regressiona(x, y),\n b = regressionb(x, y)\n\
+             output:\n mx\nend.\n",
+            missing
+        );
+        let program = crate::parse(&source).unwrap();
+        let tables = Tables::from_json(r#"{ "data/m.csv": { "table": "measurements", "columns": ["x", "y"] } }"#).unwrap();
+        let code = super::program(&program, &tables, "m.da").unwrap();
+        (program, code)
+    }
+
+    fn column(index: usize) -> Vec<f64> {
+        CSV.lines()
+            .skip(1)
+            .map(|line| line.split(',').nth(index).unwrap().parse().unwrap_or(f64::NAN))
+            .collect()
+    }
+
+    #[test]
+    fn tables_are_keyed_by_the_name_as_written() {
+        let (program, code) = query("missing = drop\n");
+        assert_eq!(program.inputs[0].name, "data/m.csv");
+        assert!(code.contains("FROM measurements;"), "{}", code);
+    }
+
+    #[test]
+    fn sqlite_computes_what_da_run_does() {
+        let (_, code) = query("missing = drop\n");
+        let Some(row) = sqlite(&code) else {
+            eprintln!("sqlite3 is not installed, the query was not run");
+            return;
+        };
+        let (x, y) = (column(0), column(1));
+        let expected = [
+            interpreter::mean(&x).unwrap(),
+            interpreter::stddev(&y).unwrap(),
+            interpreter::correlation(&x, &y).unwrap(),
+            interpreter::regressiona(&x, &y).unwrap(),
+            interpreter::regressionb(&x, &y).unwrap(),
+        ];
+        assert_eq!(row.len(), expected.len(), "{:?}", row);
+        for (value, expected) in row.iter().zip(expected) {
+            let value: f64 = value.parse().unwrap();
+            assert!((value - expected).abs() < 1e-9 * expected.abs().max(1.0), "{} is not {}", value, expected);
+        }
+    }
+
+    #[test]
+    fn a_missing_value_that_is_an_error_leaves_the_number_null() {
+        let (_, code) = query("");
+        assert!(code.contains("COUNT(measurements.y) = COUNT(*)"), "{}", code);
+        let Some(row) = sqlite(&code) else {
+            eprintln!("sqlite3 is not installed, the query was not run");
+            return;
+        };
+        // Only the mean of x, which has no missing value, is computed
+        assert_eq!(row[0], "3.5");
+        assert!(row[1..].iter().all(|value| value.is_empty()), "{:?}", row);
+    }
+
+    #[test]
+    fn zero_reads_missing_values_as_zero() {
+        let (_, code) = query("missing = zero\n");
+        assert!(code.contains("COALESCE(measurements.y, 0)"), "{}", code);
+        let Some(row) = sqlite(&code) else {
+            eprintln!("sqlite3 is not installed, the query was not run");
+            return;
+        };
+        let y: Vec<f64> = column(1).into_iter().map(|v| if v.is_nan() { 0.0 } else { v }).collect();
+        let value: f64 = row[1].parse().unwrap();
+        assert!((value - interpreter::stddev(&y).unwrap()).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,126 @@
+use crate::ast::{InputSource, OutputOp, Program, ReadOptions};
+use crate::quote::da_string;
+use crate::TokenEdition;
+
+// Every statement sits on its own line, three spaces in from its section keyword
+const INDENT: &str = "   ";
+
+// Prints a parsed program in the one canonical layout, see da fmt
+pub fn format_program(program: &Program) -> String {
+    let mut out = String::new();
+
+    if let Some(missing) = program.missing {
+        out.push_str(&format!("missing = {}\n", missing.name()));
+    }
+
+    let data: Vec<String> = program
+        .data
+        .iter()
+        .map(|def| format!("{} : {}", def.id, type_name(def.kind)))
+        .collect();
+    section(&mut out, "data", &data);
+
+    let inputs: Vec<String> = program
+        .inputs
+        .iter()
+        .map(|input| {
+            let mut args = vec![da_string(&input.file)];
+            let reader = match &input.source {
+                InputSource::Csv { header, column } => {
+                    args.push(header.to_string());
+                    args.push(column.to_string());
+                    "read"
+                }
+                InputSource::Tsv { header, column } => {
+                    args.push(header.to_string());
+                    args.push(column.to_string());
+                    "read_tsv"
+                }
+                InputSource::Json { path } => {
+                    args.push(format!("\"{}\"", path));
+                    "read_json"
+                }
+            };
+            args.extend(read_options(&input.options));
+            format!("{} = {}({})", input.id, reader, args.join(", "))
+        })
+        .collect();
+    section(&mut out, "input", &inputs);
+
+    let processes: Vec<String> = program
+        .processes
+        .iter()
+        .map(|process| format!("{} = {}({})", process.id, function_name(process.function), process.args.join(", ")))
+        .collect();
+    section(&mut out, "process", &processes);
+
+    let outputs: Vec<String> = program
+        .outputs
+        .iter()
+        .map(|output| match output {
+            OutputOp::Text(text) => format!("\"{}\"", text),
+            OutputOp::Value(id) => id.clone(),
+            OutputOp::Write { file, ids } => format!("write({}, {})", da_string(file), ids.join(", ")),
+        })
+        .collect();
+    section(&mut out, "output", &outputs);
+
+    out.push_str("end.\n");
+    out
+}
+
+// A section keyword followed by its statements, separated by commas
+fn section(out: &mut String, keyword: &str, lines: &[String]) {
+    out.push_str(keyword);
+    out.push_str(":\n");
+    for (i, line) in lines.iter().enumerate() {
+        out.push_str(INDENT);
+        out.push_str(line);
+        if i + 1 < lines.len() {
+            out.push(',');
+        }
+        out.push('\n');
+    }
+}
+
+// The named arguments of a read in a fixed order
+fn read_options(options: &ReadOptions) -> Vec<String> {
+    let mut args: Vec<String> = options
+        .entries()
+        .iter()
+        .map(|(name, value)| format!("{} = \"{}\"", name, value))
+        .collect();
+    if let Some(rows) = &options.rows {
+        match rows.end {
+            Some(end) => args.push(format!("rows = {}..{}", rows.start, end)),
+            None => args.push(format!("rows = {}..", rows.start)),
+        }
+    }
+    if options.skip_missing {
+        args.push(String::from("skip_missing = true"));
+    }
+    if let Some(missing) = options.missing {
+        args.push(format!("missing = {}", missing.name()));
+    }
+    args
+}
+
+pub fn type_name(kind: TokenEdition) -> &'static str {
+    match kind {
+        TokenEdition::NUMBER => "number",
+        TokenEdition::VECTOR => "vector",
+        other => panic!("{:?} is not a type", other),
+    }
+}
+
+// The keyword a process function is written with
+pub fn function_name(kind: TokenEdition) -> &'static str {
+    match kind {
+        TokenEdition::REGRESSIONA => "regressiona",
+        TokenEdition::REGRESSIONB => "regressionb",
+        TokenEdition::MEAN => "mean",
+        TokenEdition::STDDEV => "stddev",
+        TokenEdition::CORRELATION => "correlation",
+        other => panic!("{:?} is not a process function", other),
+    }
+}
@@ -0,0 +1,77 @@
+use std::collections::HashMap;
+
+use crate::ast::{InputSource, OutputOp, Program};
+use crate::format::{function_name, type_name};
+use crate::quote::dot_string;
+
+// The dataflow of a program as a Graphviz digraph, see da check --emit dot:
+// file columns -> vectors -> process functions -> numbers -> the output statements that show them
+pub fn dot(program: &Program, file_name: &str) -> String {
+    let mut lines = vec![
+        format!("digraph {} {{", dot_string(file_name)),
+        "    rankdir=LR;".to_string(),
+        "    node [fontname=\"Helvetica\"];".to_string(),
+    ];
+
+    // One node per declared ID, labeled with its type -> x : vector
+    for def in &program.data {
+        let label = format!("{} : {}", def.id, type_name(def.kind));
+        lines.push(format!("    {} [shape=box, label={}];", variable(&def.id), dot_string(&label)));
+    }
+
+    // Reads of the same column share its node, even when they go into different IDs
+    let mut columns: HashMap<String, String> = HashMap::new();
+    for input in &program.inputs {
+        let label = match &input.source {
+            InputSource::Csv { column, .. } | InputSource::Tsv { column, .. } => {
+                format!("{}\ncolumn {}", input.file, column)
+            }
+            InputSource::Json { path } => format!("{}\n{}", input.file, path),
+        };
+        let next = format!("column{}", columns.len());
+        let node = columns.entry(label.clone()).or_insert_with(|| {
+            lines.push(format!("    {} [shape=cylinder, label={}];", next, dot_string(&label)));
+            next
+        });
+        lines.push(format!("    {} -> {};", node, variable(&input.id)));
+    }
+
+    // Each process op is a function node of its own, a pairwise function labels its edges x and y
+    for (i, process) in program.processes.iter().enumerate() {
+        let node = format!("function{}", i);
+        lines.push(format!("    {} [shape=ellipse, label={}];", node, dot_string(function_name(process.function))));
+        match process.args.as_slice() {
+            [x, y] => {
+                lines.push(format!("    {} -> {} [label=\"x\"];", variable(x), node));
+                lines.push(format!("    {} -> {} [label=\"y\"];", variable(y), node));
+            }
+            args => args.iter().for_each(|arg| lines.push(format!("    {} -> {};", variable(arg), node))),
+        }
+        lines.push(format!("    {} -> {};", node, variable(&process.id)));
+    }
+
+    // Only output statements that show a value get a node, plain text has no data flowing into it
+    for (i, output) in program.outputs.iter().enumerate() {
+        let node = format!("output{}", i);
+        match output {
+            OutputOp::Text(_) => {}
+            OutputOp::Value(id) => {
+                lines.push(format!("    {} [shape=note, label=\"display\"];", node));
+                lines.push(format!("    {} -> {};", variable(id), node));
+            }
+            OutputOp::Write { file, ids } => {
+                let label = format!("write\n{}", file);
+                lines.push(format!("    {} [shape=note, label={}];", node, dot_string(&label)));
+                ids.iter().for_each(|id| lines.push(format!("    {} -> {};", variable(id), node)));
+            }
+        }
+    }
+
+    lines.push("}".to_string());
+    lines.join("\n") + "\n"
+}
+
+// DA IDs are letters and underscores, so they make valid node names once prefixed
+fn variable(id: &str) -> String {
+    format!("data_{}", id)
+}
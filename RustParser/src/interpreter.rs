@@ -0,0 +1,648 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+
+use serde_json::Value as Json;
+
+use crate::ast::{DataDef, InputOp, InputSource, MissingPolicy, OutputOp, ProcessOp, Program, ReadOptions};
+use crate::TokenEdition;
+
+// A runtime value, every DA variable is either a number or a vector of numbers
+#[derive(Debug, Clone)]
+pub enum Value {
+    Number(f64),
+    Vector(Vec<f64>),
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::Number(n) => write!(f, "{}", n),
+            Value::Vector(v) => {
+                // Displayed the same way Scheme displays a list -> (1 2 3)
+                let items: Vec<String> = v.iter().map(|n| n.to_string()).collect();
+                write!(f, "({})", items.join(" "))
+            }
+        }
+    }
+}
+
+// Runs a parsed program locally, the same way the generated Scheme or Prolog would, and stops at
+// the first error
+pub fn run(program: &Program) -> Result<(), String> {
+    let mut env = Environment::default();
+    for def in &program.data {
+        env.declare(def.clone());
+    }
+    env.missing = program.missing;
+
+    for input in &program.inputs {
+        env.input(input)?;
+    }
+    for process in &program.processes {
+        env.process(process)?;
+    }
+    for output in &program.outputs {
+        env.output(output)?;
+    }
+    Ok(())
+}
+
+// The declarations and values built up so far, for a whole program or statement by statement in the REPL
+#[derive(Debug, Default)]
+pub struct Environment {
+    data: Vec<DataDef>,
+    pub missing: Option<MissingPolicy>, // Program wide policy, a read can still give its own
+    values: HashMap<String, Value>,
+}
+
+impl Environment {
+    // Declaring an ID again replaces its type, a value it had is kept until it is assigned again
+    pub fn declare(&mut self, def: DataDef) {
+        self.data.retain(|declared| declared.id != def.id);
+        self.data.push(def);
+    }
+
+    pub fn input(&mut self, input: &InputOp) -> Result<(), String> {
+        check_declared(&self.data, &input.id, TokenEdition::VECTOR)?;
+        // A read without its own missing value policy follows the program wide one
+        let mut options = input.options.clone();
+        if options.missing.is_none() {
+            options.missing = self.missing;
+        }
+        self.values.insert(input.id.clone(), Value::Vector(load_input(input, &options)?));
+        Ok(())
+    }
+
+    pub fn process(&mut self, process: &ProcessOp) -> Result<(), String> {
+        let args = process
+            .args
+            .iter()
+            .map(|id| vector_arg(&self.values, id))
+            .collect::<Result<Vec<&Vec<f64>>, String>>()?;
+        let result = match process.function {
+            TokenEdition::MEAN => mean(args[0])?,
+            TokenEdition::STDDEV => stddev(args[0])?,
+            TokenEdition::CORRELATION => correlation(args[0], args[1])?,
+            TokenEdition::REGRESSIONA => regressiona(args[0], args[1])?,
+            TokenEdition::REGRESSIONB => regressionb(args[0], args[1])?,
+            other => return Err(format!("{:?} is not a process function", other)),
+        };
+        check_declared(&self.data, &process.id, TokenEdition::NUMBER)?;
+        self.values.insert(process.id.clone(), Value::Number(result));
+        Ok(())
+    }
+
+    pub fn output(&self, output: &OutputOp) -> Result<(), String> {
+        match output {
+            OutputOp::Text(text) => println!("{}", unescape(text)),
+            OutputOp::Value(id) => match self.values.get(id) {
+                Some(value) => println!("{}", value),
+                None => return Err(format!("{} is used in output but was never assigned", id)),
+            },
+            OutputOp::Write { file, ids } => write_results(file, ids, &self.values)?,
+        }
+        Ok(())
+    }
+
+    pub fn get(&self, id: &str) -> Option<&Value> {
+        self.values.get(id)
+    }
+
+    // Every declared ID with its type, in the order they were declared
+    pub fn declarations(&self) -> &[DataDef] {
+        &self.data
+    }
+}
+
+// Saves named values to a file, as a JSON object when the file ends in .json and as CSV otherwise
+pub fn write_results(file: &str, ids: &[String], env: &HashMap<String, Value>) -> Result<(), String> {
+    let values = ids
+        .iter()
+        .map(|id| env.get(id).ok_or_else(|| format!("{} is written to {} but was never assigned", id, file)))
+        .collect::<Result<Vec<&Value>, String>>()?;
+
+    let contents = if file.to_lowercase().ends_with(".json") {
+        let mut object = serde_json::Map::new();
+        for (id, value) in ids.iter().zip(&values) {
+            let json = match value {
+                Value::Number(n) => Json::from(*n),
+                Value::Vector(v) => Json::from(v.clone()),
+            };
+            object.insert(id.clone(), json);
+        }
+        serde_json::to_string_pretty(&Json::Object(object)).unwrap() + "\n"
+    } else {
+        // One column per name, a number only fills the first row and a vector fills as many rows as it has values
+        let rows = values
+            .iter()
+            .map(|value| match value {
+                Value::Number(_) => 1,
+                Value::Vector(v) => v.len(),
+            })
+            .max()
+            .unwrap_or(0);
+        let mut lines = vec![ids.join(",")];
+        for row in 0..rows {
+            let cells: Vec<String> = values
+                .iter()
+                .map(|value| match value {
+                    Value::Number(n) if row == 0 => n.to_string(),
+                    Value::Vector(v) if row < v.len() && !v[row].is_nan() => v[row].to_string(),
+                    _ => String::new(),
+                })
+                .collect();
+            lines.push(cells.join(","));
+        }
+        lines.join("\n") + "\n"
+    };
+
+    fs::write(file, contents).map_err(|e| format!("Could not write {}: {}", file, e))
+}
+
+// Every assigned ID has to be declared in the data section with the matching type
+fn check_declared(data: &[DataDef], id: &str, kind: TokenEdition) -> Result<(), String> {
+    match data.iter().find(|def| def.id == id) {
+        Some(def) if def.kind == kind => Ok(()),
+        Some(def) => Err(format!("{} is declared as {:?} but is assigned a {:?}", id, def.kind, kind)),
+        None => Err(format!("{} is assigned but never declared in the data section", id)),
+    }
+}
+
+fn vector_arg<'a>(env: &'a HashMap<String, Value>, id: &str) -> Result<&'a Vec<f64>, String> {
+    match env.get(id) {
+        Some(Value::Vector(v)) => Ok(v),
+        Some(Value::Number(_)) => Err(format!("{} is a number, but a vector was expected", id)),
+        None => Err(format!("{} is used before it was assigned", id)),
+    }
+}
+
+fn load_input(input: &InputOp, options: &ReadOptions) -> Result<Vec<f64>, String> {
+    match &input.source {
+        InputSource::Csv { header, column } => read_column(&input.file, *header, *column, options),
+        InputSource::Tsv { header, column } => {
+            let options = ReadOptions {
+                delimiter: Some("\\t".to_string()),
+                ..options.clone()
+            };
+            read_column(&input.file, *header, *column, &options)
+        }
+        InputSource::Json { path } => read_json(&input.file, path, options),
+    }
+}
+
+// Reads one column of a delimited file, skipping the first row when it is a header
+pub fn read_column(path: &str, header: bool, column: usize, options: &ReadOptions) -> Result<Vec<f64>, String> {
+    let bytes = fs::read(path).map_err(|e| format!("Could not read {}: {}", path, e))?;
+    let text = decode(&bytes, options.encoding.as_deref())?;
+    let delimiter = single_char(options.delimiter.as_deref(), ',', "delimiter")?;
+    let quote = single_char(options.quote.as_deref(), '"', "quote")?;
+
+    let mut values = Vec::new();
+    for (row, record) in split_records(&text, delimiter, quote).iter().enumerate() {
+        if header && row == 0 {
+            continue;
+        }
+        let data_row = if header { row - 1 } else { row };
+        if options.rows.is_some_and(|rows| !rows.contains(data_row)) {
+            continue;
+        }
+        // A short row counts as a missing cell
+        let field = record.get(column).map(|field| field.trim()).unwrap_or("");
+        if is_missing(field) {
+            if !options.skip_missing {
+                let place = format!("{} row {} column {}", path, row + 1, column);
+                values.push(missing_value(options.missing, &place)?);
+            }
+            continue;
+        }
+        match field.parse::<f64>() {
+            Ok(n) => values.push(n),
+            Err(_) => return Err(format!("{} row {} column {}: {:?} is not a number", path, row + 1, column, field)),
+        }
+    }
+    Ok(values)
+}
+
+// The names in the first row of a delimited file, read with the same options as its values
+pub fn read_header(path: &str, options: &ReadOptions) -> Result<Vec<String>, String> {
+    let bytes = fs::read(path).map_err(|e| format!("Could not read {}: {}", path, e))?;
+    let text = decode(&bytes, options.encoding.as_deref())?;
+    let delimiter = single_char(options.delimiter.as_deref(), ',', "delimiter")?;
+    let quote = single_char(options.quote.as_deref(), '"', "quote")?;
+    let records = split_records(&text, delimiter, quote);
+    Ok(records.into_iter().next().unwrap_or_default().into_iter().map(|name| name.trim().to_string()).collect())
+}
+
+// Reads every number selected by a JSON path such as $.items[*].price
+pub fn read_json(file: &str, path: &str, options: &ReadOptions) -> Result<Vec<f64>, String> {
+    let bytes = fs::read(file).map_err(|e| format!("Could not read {}: {}", file, e))?;
+    let text = decode(&bytes, options.encoding.as_deref())?;
+    let document: Json = serde_json::from_str(&text).map_err(|e| format!("{} is not valid JSON: {}", file, e))?;
+
+    let mut values = Vec::new();
+    for (index, node) in select(&document, path)?.into_iter().enumerate() {
+        if options.rows.is_some_and(|rows| !rows.contains(index)) {
+            continue;
+        }
+        if node.is_null() {
+            if !options.skip_missing {
+                let place = format!("{} {} value {}", file, path, index);
+                values.push(missing_value(options.missing, &place)?);
+            }
+            continue;
+        }
+        match node {
+            // A path that ends on an array of numbers selects all of them
+            Json::Array(items) => {
+                for item in items {
+                    values.push(json_number(file, path, item)?);
+                }
+            }
+            other => values.push(json_number(file, path, other)?),
+        }
+    }
+    Ok(values)
+}
+
+// Blank cells and the usual spellings of "not available" are missing values
+fn is_missing(field: &str) -> bool {
+    let lower = field.to_lowercase();
+    field.is_empty() || lower == "na" || lower == "n/a" || lower == "nan" || lower == "null"
+}
+
+// The value a missing cell is read as, a dropped cell is kept as NaN so vectors read from the
+// same file stay aligned until a statistic leaves it out
+fn missing_value(policy: Option<MissingPolicy>, place: &str) -> Result<f64, String> {
+    match policy {
+        Some(MissingPolicy::Drop) => Ok(f64::NAN),
+        Some(MissingPolicy::Zero) => Ok(0.0),
+        Some(MissingPolicy::Error) | None => Err(format!(
+            "{} is missing, use missing = drop or missing = zero to allow missing values",
+            place
+        )),
+    }
+}
+
+fn json_number(file: &str, path: &str, node: &Json) -> Result<f64, String> {
+    node.as_f64().ok_or_else(|| format!("{} {}: {} is not a number", file, path, node))
+}
+
+// Evaluates the supported subset of JSON path -> $ .name ['name'] [n] [*] .*
+fn select<'a>(document: &'a Json, path: &str) -> Result<Vec<&'a Json>, String> {
+    if !path.starts_with('$') {
+        return Err(format!("A JSON path has to start with $, found {}", path));
+    }
+    let chars: Vec<char> = path.chars().collect();
+    let mut nodes = vec![document];
+    let mut i = 1;
+
+    while i < chars.len() {
+        let step: String;
+        if chars[i] == '.' {
+            let start = i + 1;
+            i = start;
+            while i < chars.len() && chars[i] != '.' && chars[i] != '[' {
+                i += 1;
+            }
+            step = chars[start..i].iter().collect();
+        } else if chars[i] == '[' {
+            let start = i + 1;
+            while i < chars.len() && chars[i] != ']' {
+                i += 1;
+            }
+            if i == chars.len() {
+                return Err(format!("Unclosed [ in JSON path {}", path));
+            }
+            step = chars[start..i].iter().collect::<String>().trim_matches(|c| c == '\'' || c == '"').to_string();
+            i += 1;
+        } else {
+            return Err(format!("Unexpected {} in JSON path {}", chars[i], path));
+        }
+
+        let mut next = Vec::new();
+        for node in nodes {
+            if step == "*" {
+                match node {
+                    Json::Array(items) => next.extend(items.iter()),
+                    Json::Object(map) => next.extend(map.values()),
+                    _ => {}
+                }
+            } else if let (Json::Array(items), Ok(index)) = (node, step.parse::<usize>()) {
+                next.extend(items.get(index));
+            } else if let Some(child) = node.get(step.as_str()) {
+                next.push(child);
+            }
+        }
+        nodes = next;
+    }
+    Ok(nodes)
+}
+
+// Turns the raw bytes of a file into text, utf-8 is assumed unless told otherwise
+fn decode(bytes: &[u8], encoding: Option<&str>) -> Result<String, String> {
+    match encoding.map(|e| e.to_lowercase()).as_deref() {
+        None | Some("utf-8") | Some("utf8") => Ok(String::from_utf8_lossy(bytes).into_owned()),
+        Some("latin1") | Some("latin-1") | Some("iso-8859-1") => Ok(bytes.iter().map(|&b| b as char).collect()),
+        Some(other) => Err(format!("Unsupported encoding {}, use utf-8 or latin1", other)),
+    }
+}
+
+fn single_char(option: Option<&str>, default: char, name: &str) -> Result<char, String> {
+    match option {
+        None => Ok(default),
+        Some(value) => {
+            let value = unescape(value);
+            let mut chars = value.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => Ok(c),
+                _ => Err(format!("The {} must be a single character, found {:?}", name, value)),
+            }
+        }
+    }
+}
+
+// Splits text into records and fields, a quoted field may contain the delimiter, newlines
+// and doubled quote characters
+fn split_records(text: &str, delimiter: char, quote: char) -> Vec<Vec<String>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quote = false;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quote {
+            if c == quote {
+                if chars.peek() == Some(&quote) {
+                    field.push(quote);
+                    chars.next();
+                } else {
+                    in_quote = false;
+                }
+            } else {
+                field.push(c);
+            }
+        } else if c == quote {
+            in_quote = true;
+        } else if c == delimiter {
+            record.push(field.clone());
+            field.clear();
+        } else if c == '\n' || c == '\r' {
+            if c == '\r' && chars.peek() == Some(&'\n') {
+                chars.next();
+            }
+            record.push(field.clone());
+            field.clear();
+            // Blank lines are skipped
+            if !(record.len() == 1 && record[0].is_empty()) {
+                records.push(record.clone());
+            }
+            record.clear();
+        } else {
+            field.push(c);
+        }
+    }
+
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    records
+}
+
+// Resolves backslash escapes of a DA string literal -> "\t" becomes a tab
+pub fn unescape(val: &str) -> String {
+    let mut out = String::new();
+    let mut chars = val.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('t') => out.push('\t'),
+            Some('n') => out.push('\n'),
+            Some('r') => out.push('\r'),
+            Some(other) => out.push(other), // \\ \" \' and anything else stand for themselves
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+// Missing values dropped while reading are NaN, every statistic leaves them out
+fn present(x: &[f64]) -> Vec<f64> {
+    x.iter().copied().filter(|v| !v.is_nan()).collect()
+}
+
+// Pairwise functions keep only the pairs where both values are present
+fn complete_pairs(x: &[f64], y: &[f64]) -> Result<(Vec<f64>, Vec<f64>), String> {
+    if x.len() != y.len() {
+        return Err(format!(
+            "Pairwise functions need vectors of the same length, found {} and {}",
+            x.len(),
+            y.len()
+        ));
+    }
+    Ok(x.iter()
+        .zip(y)
+        .filter(|(a, b)| !a.is_nan() && !b.is_nan())
+        .map(|(a, b)| (*a, *b))
+        .unzip())
+}
+
+pub fn mean(x: &[f64]) -> Result<f64, String> {
+    let x = present(x);
+    if x.is_empty() {
+        return Err(String::from("mean of an empty vector"));
+    }
+    Ok(x.iter().sum::<f64>() / x.len() as f64)
+}
+
+// Sample standard deviation
+pub fn stddev(x: &[f64]) -> Result<f64, String> {
+    let x = present(x);
+    if x.len() < 2 {
+        return Err(String::from("stddev needs at least two values"));
+    }
+    let m = mean(&x)?;
+    let sum_sq: f64 = x.iter().map(|v| (v - m) * (v - m)).sum();
+    Ok((sum_sq / (x.len() - 1) as f64).sqrt())
+}
+
+// Pearson correlation coefficient
+pub fn correlation(x: &[f64], y: &[f64]) -> Result<f64, String> {
+    let (x, y) = complete_pairs(x, y)?;
+    let (sxx, syy, sxy) = sums_of_squares(&x, &y)?;
+    Ok(sxy / (sxx * syy).sqrt())
+}
+
+// Intercept a of the least squares line y = a + bx
+pub fn regressiona(x: &[f64], y: &[f64]) -> Result<f64, String> {
+    let (x, y) = complete_pairs(x, y)?;
+    Ok(mean(&y)? - regressionb(&x, &y)? * mean(&x)?)
+}
+
+// Slope b of the least squares line y = a + bx
+pub fn regressionb(x: &[f64], y: &[f64]) -> Result<f64, String> {
+    let (x, y) = complete_pairs(x, y)?;
+    let (sxx, _, sxy) = sums_of_squares(&x, &y)?;
+    Ok(sxy / sxx)
+}
+
+fn sums_of_squares(x: &[f64], y: &[f64]) -> Result<(f64, f64, f64), String> {
+    let (mx, my) = (mean(x)?, mean(y)?);
+    let mut sxx = 0.0;
+    let mut syy = 0.0;
+    let mut sxy = 0.0;
+    for (a, b) in x.iter().zip(y) {
+        sxx += (a - mx) * (a - mx);
+        syy += (b - my) * (b - my);
+        sxy += (a - mx) * (b - my);
+    }
+    Ok((sxx, syy, sxy))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::RowRange;
+
+    fn close(value: f64, expected: f64) -> bool {
+        (value - expected).abs() < 1e-12
+    }
+
+    // A file under the system temp dir holding text, removed again by the caller
+    fn scratch(name: &str, text: &str) -> String {
+        let path = std::env::temp_dir().join(format!("da-interpreter-{}-{}", name, std::process::id()));
+        fs::write(&path, text).unwrap();
+        path.to_string_lossy().into_owned()
+    }
+
+    #[test]
+    fn statistics() {
+        let x = [1.0, 2.0, 3.0, 4.0];
+        let y = [2.0, 4.0, 6.0, 8.5];
+        assert!(close(mean(&x).unwrap(), 2.5));
+        assert!(close(stddev(&x).unwrap(), (5.0f64 / 3.0).sqrt()));
+        assert!(close(regressionb(&x, &x).unwrap(), 1.0));
+        assert!(close(regressiona(&x, &x.map(|v| 3.0 + 2.0 * v)).unwrap(), 3.0));
+        assert!(close(correlation(&x, &x.map(|v| -v)).unwrap(), -1.0));
+        assert!(correlation(&x, &y).unwrap() > 0.99);
+    }
+
+    #[test]
+    fn statistics_leave_out_missing_values() {
+        let x = [1.0, f64::NAN, 3.0];
+        let y = [2.0, 5.0, f64::NAN];
+        assert!(close(mean(&x).unwrap(), 2.0));
+        assert_eq!(complete_pairs(&x, &y).unwrap(), (vec![1.0], vec![2.0]));
+    }
+
+    #[test]
+    fn statistics_reject_too_few_values() {
+        assert!(mean(&[]).is_err());
+        assert!(mean(&[f64::NAN]).is_err());
+        assert!(stddev(&[1.0]).is_err());
+        assert!(correlation(&[1.0, 2.0], &[1.0]).is_err());
+    }
+
+    #[test]
+    fn unescape_resolves_backslashes() {
+        assert_eq!(unescape(r"a\tb\nc\r"), "a\tb\nc\r");
+        assert_eq!(unescape(r#"it\"s \\ \'"#), "it\"s \\ '");
+        assert_eq!(unescape(r"end\"), "end\\");
+    }
+
+    #[test]
+    fn records_keep_quoted_delimiters_and_skip_blank_lines() {
+        let records = split_records("a,\"b,c\"\r\n\n\"say \"\"hi\"\"\",2", ',', '"');
+        assert_eq!(records, vec![vec!["a", "b,c"], vec!["say \"hi\"", "2"]]);
+    }
+
+    #[test]
+    fn missing_cells() {
+        for field in ["", "NA", "n/a", "NaN", "null"] {
+            assert!(is_missing(field), "{:?}", field);
+        }
+        assert!(!is_missing("0"));
+        assert!(missing_value(Some(MissingPolicy::Drop), "x").unwrap().is_nan());
+        assert_eq!(missing_value(Some(MissingPolicy::Zero), "x").unwrap(), 0.0);
+        assert!(missing_value(None, "x").is_err());
+    }
+
+    #[test]
+    fn read_column_applies_the_options() {
+        let path = scratch("column", "x;'y;z'\n1;'10'\n2;20\n");
+        let options = ReadOptions {
+            delimiter: Some(String::from(";")),
+            quote: Some(String::from("'")),
+            ..ReadOptions::default()
+        };
+        assert_eq!(read_column(&path, true, 1, &options).unwrap(), vec![10.0, 20.0]);
+        assert_eq!(read_header(&path, &options).unwrap(), vec!["x", "y;z"]);
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn read_column_follows_the_missing_value_policy() {
+        let path = scratch("missing", "x\n1\nNA\n3\n");
+        let mut options = ReadOptions::default();
+        assert!(read_column(&path, true, 0, &options).is_err());
+        options.missing = Some(MissingPolicy::Zero);
+        assert_eq!(read_column(&path, true, 0, &options).unwrap(), vec![1.0, 0.0, 3.0]);
+        options.missing = Some(MissingPolicy::Drop);
+        assert!(read_column(&path, true, 0, &options).unwrap()[1].is_nan());
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn read_column_keeps_the_rows_in_range() {
+        let path = scratch("rows", "x,y\n1,10\n2,\n3,30\n4\n5,50\n");
+        let mut options = ReadOptions { skip_missing: true, ..ReadOptions::default() };
+        assert_eq!(read_column(&path, true, 1, &options).unwrap(), vec![10.0, 30.0, 50.0]);
+        options.rows = Some(RowRange { start: 1, end: Some(3) });
+        assert_eq!(read_column(&path, true, 1, &options).unwrap(), vec![30.0]);
+        options.rows = Some(RowRange { start: 3, end: None });
+        assert_eq!(read_column(&path, true, 0, &options).unwrap(), vec![4.0, 5.0]);
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn read_json_selects_a_path() {
+        let path = scratch("json", r#"{ "items": [{ "price": 1.5 }, { "price": 2 }, { "price": 3 }] }"#);
+        let options = ReadOptions::default();
+        assert_eq!(read_json(&path, "$.items[*].price", &options).unwrap(), vec![1.5, 2.0, 3.0]);
+        assert_eq!(read_json(&path, "$['items'][1].price", &options).unwrap(), vec![2.0]);
+        assert!(read_json(&path, "items", &options).is_err());
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn write_results_as_csv_and_json() {
+        let env = HashMap::from([
+            (String::from("m"), Value::Number(2.5)),
+            (String::from("x"), Value::Vector(vec![1.0, f64::NAN, 3.0])),
+        ]);
+        let ids = [String::from("m"), String::from("x")];
+        let csv = scratch("write", "");
+        write_results(&csv, &ids, &env).unwrap();
+        assert_eq!(fs::read_to_string(&csv).unwrap(), "m,x\n2.5,1\n,\n,3\n");
+
+        // Only a file ending in .json is written as JSON
+        let json = format!("{}.json", csv);
+        write_results(&json, &ids[..1], &env).unwrap();
+        assert_eq!(fs::read_to_string(&json).unwrap(), "{\n  \"m\": 2.5\n}\n");
+        assert!(write_results(&json, &[String::from("y")], &env).is_err());
+        fs::remove_file(csv).unwrap();
+        fs::remove_file(json).unwrap();
+    }
+
+    #[test]
+    fn run_reports_a_file_it_cannot_read() {
+        let program = crate::parse("data:\n x : vector,\n m : number\ninput:\n x = read(\"none.csv\", false, 0)\nprocess:\n m = mean(x)\noutput:\n m\nend.\n").unwrap();
+        let error = run(&program).unwrap_err();
+        assert!(error.contains("none.csv"), "{}", error);
+    }
+}
@@ -0,0 +1,1345 @@
+// The DA front end as a library: the scanner, the parser, the code generators and the interpreter
+//
+// Scanning and parsing stop at the first error in a DA program ("hide the head in the sand"), which
+// every function below returns as a Diagnostic with the place in the source it was found at.
+
+extern crate regex;
+
+pub mod ast;
+pub mod codegen;
+pub mod format;
+pub mod graph;
+pub mod interpreter;
+pub mod names;
+pub mod paths;
+pub mod quote;
+pub mod runtime;
+
+use std::fmt;
+
+use regex::Regex;
+use serde::Serialize;
+
+use ast::{
+    DataDef, InputOp, InputSource, MissingPolicy, OutputOp, ProcessOp, Program, ReadOptions, RowRange, Statement,
+};
+use codegen::sql::Tables;
+use interpreter::unescape;
+use names::Names;
+use paths::PathBase;
+
+// Every language Store::generate and compile can produce
+pub const TARGETS: [&str; 7] = ["scheme", "prolog", "javascript", "sql", "julia", "octave", "rust"];
+
+// File extension of the code generated for a target, None when it is not one of the TARGETS
+pub fn extension(target: &str) -> Option<&'static str> {
+    match target {
+        "scheme" => Some("scm"),
+        "prolog" => Some("pl"),
+        "javascript" => Some("mjs"),
+        "sql" => Some("sql"),
+        "julia" => Some("jl"),
+        "octave" => Some("m"),
+        "rust" => Some("rs"),
+        _ => None,
+    }
+}
+
+// The first lexical, syntax or runtime error found in a DA program
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    message: String,
+    position: Option<Position>, // Known when the error came from scanning or parsing a Store
+}
+
+impl Diagnostic {
+    // An error without a place in the source, such as one found while generating code or running it
+    pub fn new(message: impl Into<String>) -> Self {
+        Self { message: message.into(), position: None }
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn position(&self) -> Option<Position> {
+        self.position
+    }
+
+    // The same diagnostic found at the given place in the source
+    pub fn at(mut self, position: Position) -> Self {
+        self.position = Some(position);
+        self
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for Diagnostic {}
+
+// The errors of the code generators and the interpreter, which have no place in the source
+impl From<String> for Diagnostic {
+    fn from(message: String) -> Self {
+        Diagnostic::new(message)
+    }
+}
+
+// The tokens of a DA program
+pub fn lex(source: &str) -> Result<Vec<Token>, Diagnostic> {
+    let mut store = Store::new(source.to_string(), String::from("<source>"));
+    store.lex_source()?;
+    Ok(store.tokens)
+}
+
+// The parsed form of a DA program
+pub fn parse(source: &str) -> Result<Program, Diagnostic> {
+    let mut store = Store::new(source.to_string(), String::from("<source>"));
+    store.lex_and_parse_source()?;
+    Ok(store.program)
+}
+
+// One statement on its own, None for a line with nothing to run such as a section header
+pub fn parse_statement(source: &str) -> Result<Option<Statement>, Diagnostic> {
+    let mut store = Store::new(source.to_string(), String::from("<source>"));
+    store.lex_and_parse_statement()
+}
+
+// Runs lexical and syntax analysis and reports the first error, if any
+pub fn check(source: &str) -> Result<(), Diagnostic> {
+    parse(source).map(|_| ())
+}
+
+// The program reprinted in the canonical layout of da fmt
+pub fn format(source: &str) -> Result<String, Diagnostic> {
+    parse(source).map(|program| format::format_program(&program))
+}
+
+// The generated program for one of the TARGETS
+pub fn compile(source: &str, target: &str) -> Result<String, Diagnostic> {
+    let mut store = Store::new(source.to_string(), String::from("<source>"));
+    store.lex_and_parse_source()?;
+    store.generate(target)
+}
+
+// The generated program with the runtime it needs in front of it, see runtime::runtime
+pub fn compile_standalone(source: &str, target: &str) -> Result<String, Diagnostic> {
+    let mut store = Store::new(source.to_string(), String::from("<source>"));
+    store.lex_and_parse_source()?;
+    store.generate_standalone(target)
+}
+
+#[derive(Debug, PartialEq, Clone, Copy, Serialize)]
+pub enum TokenEdition {
+    DATA,
+    INPUT,
+    PROCESS,
+    OUTPUT,
+    END,
+    ID,
+    NUM,
+    TRUE,
+    FALSE,
+    READ,
+    READTSV,
+    READJSON,
+    COLON,
+    COMMA,
+    PERIOD,
+    LPAREN,
+    RPAREN,
+    ASSIGN,
+    VECTOR,
+    NUMBER,
+    REGRESSIONA,
+    REGRESSIONB,
+    MEAN,
+    STDDEV,
+    CORRELATION,
+    STRING,
+    WRITE,
+}
+
+impl TokenEdition {
+    // How the token is spelled in a DA program, or what it stands for -> expected ) but found ,
+    fn describe(self) -> &'static str {
+        match self {
+            TokenEdition::DATA => "data",
+            TokenEdition::INPUT => "input",
+            TokenEdition::PROCESS => "process",
+            TokenEdition::OUTPUT => "output",
+            TokenEdition::END => "end",
+            TokenEdition::ID => "an ID",
+            TokenEdition::NUM => "a number",
+            TokenEdition::TRUE => "true",
+            TokenEdition::FALSE => "false",
+            TokenEdition::READ => "read",
+            TokenEdition::READTSV => "read_tsv",
+            TokenEdition::READJSON => "read_json",
+            TokenEdition::COLON => ":",
+            TokenEdition::COMMA => ",",
+            TokenEdition::PERIOD => ".",
+            TokenEdition::LPAREN => "(",
+            TokenEdition::RPAREN => ")",
+            TokenEdition::ASSIGN => "=",
+            TokenEdition::VECTOR => "vector",
+            TokenEdition::NUMBER => "number",
+            TokenEdition::REGRESSIONA => "regressiona",
+            TokenEdition::REGRESSIONB => "regressionb",
+            TokenEdition::MEAN => "mean",
+            TokenEdition::STDDEV => "stddev",
+            TokenEdition::CORRELATION => "correlation",
+            TokenEdition::STRING => "a string",
+            TokenEdition::WRITE => "write",
+        }
+    }
+}
+
+// Where a token starts in the source, both counted from 1
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Token {
+    kind: TokenEdition,
+    literal: String,
+    position: Position,
+}
+
+impl Token {
+    pub fn new(kind: TokenEdition, literal: String) -> Self {
+        Self { kind, literal, position: Position::default() }
+    }
+
+    // The same token starting at the given place in the source
+    pub fn at(mut self, position: Position) -> Self {
+        self.position = position;
+        self
+    }
+
+    pub fn position(&self) -> Position {
+        self.position
+    }
+
+    pub fn kind(&self) -> TokenEdition {
+        self.kind
+    }
+
+    // The text of the token as it appeared in the source, strings keep their quotes
+    pub fn literal(&self) -> &str {
+        &self.literal
+    }
+}
+
+// Holds a DA program through scanning, parsing and code generation
+#[derive(Debug)]
+pub struct Store {
+    source_code: String,
+    current_index: usize,
+    tokens: Vec<Token>,
+    scheme_output: Vec<String>,
+    pl_output: Vec<String>,
+    file_name: String, // Shown in the header comment of the generated code
+    verbose: bool, // Prints progress messages to stderr, stdout only ever carries the requested output
+    program: Program,
+    position: Position, // The char the scanner is at
+    lexed: bool,
+    names: Names, // What each ID is called in the generated code
+    paths: PathBase, // What the file names in read and write are relative to
+    tables: Tables, // The database tables CSV files were exported from, for the sql target
+}
+
+impl Store {
+    pub fn new(source_code: String, file_name: String) -> Self {
+        Self {
+            file_name,
+            verbose: false,
+            source_code: source_code.chars().collect(),
+            current_index: 0,
+            tokens: Vec::new(),
+            scheme_output: Vec::new(),
+            pl_output: Vec::new(),
+            program: Program::default(),
+            position: Position::default(),
+            lexed: false,
+            names: Names::default(),
+            paths: PathBase::default(),
+            tables: Tables::default(),
+        }
+    }
+
+    pub fn lex_and_parse_source(&mut self) -> Result<(), Diagnostic> {
+        self.lex_source()?;
+        self.program_syntax()
+    }
+
+    pub fn lex_source(&mut self) -> Result<(), Diagnostic> {
+        let mut temp = String::from("");
+        let mut in_quote = false; // a bool value if inside a quote
+        let mut quote_count = 0; // a tracker on how many quotes have been counted (max will be 2, min 0)
+        let mut in_parens = false; // bool if inside a parenthesis
+        let mut escaped = false; // bool if the previous char inside a quote was a backslash
+
+        let mut number_added = false; // If we have a number, it can be either 1 or more digits
+        let mut num_length = 0; // These variables will help continue a loop if a number has a following number
+        let mut check_length = 0;
+
+
+
+        if self.verbose {
+            eprintln!("Lexical Analysis Beginning...");
+        }
+
+        let value: Vec<char> = self.source_code.chars().collect();
+        let mut line = 1;
+        let mut column = 1;
+        let mut start = Position::default(); // Where the text collected in temp began
+
+        for (i, &c) in value.iter().enumerate() {
+            let here = Position { line, column };
+            self.position = here;
+            if c == 0xA as char {
+                line += 1;
+                column = 1;
+            } else {
+                column += 1;
+            }
+
+            if number_added {
+                check_length += 1;
+                if check_length == num_length {
+                    number_added = false;
+                    num_length = 0;
+                    check_length = 0;
+                } else {
+                    continue;
+                }
+            }
+
+            if c == 0xA as char {
+                // If we reach a newline, and our concat string is not empty, we have an ID value
+                if !temp.is_empty() {
+                    self.tokens
+                        .push(Token::new(TokenEdition::ID, temp.to_string()).at(start));
+                    temp.clear();
+                }
+                temp.clear();
+                continue;
+            }
+            if c == ' ' && !in_quote {
+                // If we get to an empty char, skip the loop and continue (unless inside of a quote)
+                continue;
+            }
+
+            if in_quote && escaped {
+                // The char after a backslash is part of the string, even if it is a quote
+                temp.push(c);
+                escaped = false;
+                continue;
+            }
+            if in_quote && c == '\\' {
+                temp.push(c);
+                escaped = true;
+                continue;
+            }
+
+            if c == 34 as char {
+                // if we find a char that is an quote ", we are inside of a quote
+                if temp.is_empty() {
+                    start = here;
+                }
+                temp.push(c);
+                quote_count += 1;
+                // println!("Found an open quote ... {} ... {}" , temp, quote_count);
+                in_quote = true;
+                if quote_count == 2 {
+                    // If we find a second quote, this means that our string has been enclosed and we push it to our tokens
+                    let mut string_valid = temp.clone();
+
+                    string_valid.remove(0);
+                    string_valid.pop();
+
+                    self.string_check(&string_valid)?;
+
+                        self.tokens
+                        .push(Token::new(TokenEdition::STRING, temp.to_string()).at(start));
+                        temp.clear();
+                        in_quote = false;
+                        quote_count = 0;
+                    
+                }
+                continue;
+            }
+
+            if temp.is_empty() {
+                start = here;
+            }
+            temp.push(c);
+
+            if in_quote {
+                // Anything else inside of a quote belongs to the string
+                continue;
+            }
+
+            if c == ',' && !in_quote {
+                if in_parens {
+                    temp.pop();
+                    // in some cases, after reaching a comma, we can either have a truthy value (t/f) or an ID
+                    // or simply no value, these cases handle those possibilities.
+                    if temp == "false" {
+                        self.tokens
+                            .push(Token::new(TokenEdition::FALSE, temp.to_string()).at(start));
+                    } else if temp == "true" {
+                        self.tokens
+                            .push(Token::new(TokenEdition::TRUE, temp.to_string()).at(start));
+                    } else if !temp.is_empty() {
+                        self.tokens
+                            .push(Token::new(TokenEdition::ID, temp.to_string()).at(start));
+                    }
+                    temp.clear();
+                    self.tokens
+                        .push(Token::new(TokenEdition::COMMA, c.to_string()).at(here));
+                    continue;
+                }
+
+                // if not inside a parentheses, this will handle the cases
+                temp.pop();
+                if !temp.is_empty() {
+                    let mut check_temp = temp.clone();
+                    check_temp = check_temp.to_lowercase();
+                    if check_temp != temp {
+                        return Err(self.error(format!(
+                            "Found an invalid value!  {}  is not accepted as a value",
+                            temp
+                        )));
+                    }
+                    self.tokens
+                        .push(Token::new(TokenEdition::ID, temp.to_string()).at(start));
+                }
+
+                self.tokens
+                    .push(Token::new(TokenEdition::COMMA, c.to_string()).at(here));
+                temp.clear();
+            }
+            if c == '(' {
+                self.tokens
+                    .push(Token::new(TokenEdition::LPAREN, c.to_string()).at(here));
+                in_parens = true;
+                temp.clear();
+            }
+            if c == ')' {
+                temp.pop();
+                if !temp.is_empty() {
+                    self.tokens
+                        .push(Token::new(TokenEdition::ID, temp.to_string()).at(start));
+                }
+                self.tokens
+                    .push(Token::new(TokenEdition::RPAREN, c.to_string()).at(here));
+                in_parens = false;
+                temp.clear();
+            }
+            if c == '.' && !in_quote {
+                self.tokens
+                    .push(Token::new(TokenEdition::PERIOD, c.to_string()).at(here));
+                temp.clear();
+            }
+            if c == '=' && !in_quote {
+                temp.pop();
+                if !temp.is_empty() {
+                    self.tokens
+                        .push(Token::new(TokenEdition::ID, temp.to_string()).at(start));
+                }
+
+                self.tokens
+                    .push(Token::new(TokenEdition::ASSIGN, c.to_string()).at(here));
+                temp.clear();
+            }
+            // if any of these key words are found, this will create a token for them and add it to our vector of tokens
+            if temp == "data" {
+                self.tokens
+                    .push(Token::new(TokenEdition::DATA, temp.to_string()).at(start));
+                // println!("{}" , temp);
+                temp.clear();
+            }
+            if temp == "input" {
+                self.tokens
+                    .push(Token::new(TokenEdition::INPUT, temp.to_string()).at(start));
+                // println!("{}" , temp);
+                temp.clear();
+            }
+            if temp == "process" {
+                self.tokens
+                    .push(Token::new(TokenEdition::PROCESS, temp.to_string()).at(start));
+                temp.clear();
+            }
+            if temp == "output" {
+                self.tokens
+                    .push(Token::new(TokenEdition::OUTPUT, temp.to_string()).at(start));
+                temp.clear();
+            }
+            if temp == "end" {
+                self.tokens
+                    .push(Token::new(TokenEdition::END, temp.to_string()).at(start));
+                temp.clear();
+            }
+            if c == ':' {
+                temp.pop();
+                if !temp.is_empty() {
+                    self.tokens
+                        .push(Token::new(TokenEdition::ID, temp.to_string()).at(start));
+                    temp.clear();
+                }
+                self.tokens
+                    .push(Token::new(TokenEdition::COLON, c.to_string()).at(here));
+            }
+            // If we reach any of the below key words, this will append them...
+            if temp == "read" && value.get(i + 1) != Some(&'_') {
+                // read_tsv and read_json also begin with read, so a following underscore keeps building the keyword
+                self.tokens
+                    .push(Token::new(TokenEdition::READ, temp.to_string()).at(start));
+                temp.clear();
+            }
+            if temp == "read_tsv" {
+                self.tokens
+                    .push(Token::new(TokenEdition::READTSV, temp.to_string()).at(start));
+                temp.clear();
+            }
+            if temp == "read_json" {
+                self.tokens
+                    .push(Token::new(TokenEdition::READJSON, temp.to_string()).at(start));
+                temp.clear();
+            }
+            if temp == "write" {
+                self.tokens
+                    .push(Token::new(TokenEdition::WRITE, temp.to_string()).at(start));
+                temp.clear();
+            }
+            if temp == "number" {
+                self.tokens
+                    .push(Token::new(TokenEdition::NUMBER, temp.to_string()).at(start));
+                temp.clear();
+            }
+            if temp == "vector" {
+                self.tokens
+                    .push(Token::new(TokenEdition::VECTOR, temp.to_string()).at(start));
+                temp.clear();
+            }
+            if temp == "regressiona" {
+                self.tokens
+                    .push(Token::new(TokenEdition::REGRESSIONA, temp.to_string()).at(start));
+                temp.clear();
+            }
+            if temp == "regressionb" {
+                self.tokens
+                    .push(Token::new(TokenEdition::REGRESSIONB, temp.to_string()).at(start));
+                temp.clear();
+            }
+            if temp == "correlation" {
+                self.tokens
+                    .push(Token::new(TokenEdition::CORRELATION, temp.to_string()).at(start));
+                temp.clear();
+            }
+            if temp == "false" {
+                self.tokens
+                    .push(Token::new(TokenEdition::FALSE, temp.to_string()).at(start));
+                temp.clear();
+            }
+            if temp == "true" {
+                self.tokens
+                    .push(Token::new(TokenEdition::TRUE, temp.to_string()).at(start));
+                temp.clear();
+            }
+            if temp == "stddev" {
+                self.tokens
+                    .push(Token::new(TokenEdition::STDDEV, temp.to_string()).at(start));
+                temp.clear();
+            }
+            if temp == "mean" {
+                self.tokens
+                    .push(Token::new(TokenEdition::MEAN, temp.to_string()).at(start));
+                temp.clear();
+            }
+            if c.is_numeric() && !in_quote {
+                // a numeric value symbolizes a value within the read function, this will add that value as a token
+                number_added = true;
+                let mut temp_builder = String::from("");
+                let mut j = i;
+                while j < value.len() && value[j].is_numeric() {
+                    temp_builder.push(value[j]);
+                    j += 1;
+                    num_length += 1;
+                }
+                self.tokens
+                    .push(Token::new(TokenEdition::NUM, temp_builder.to_string()).at(here));
+                temp.clear();
+            }
+        }
+        // A program cut off while typing ends in the middle of a string or an ID
+        if in_quote {
+            return Err(self.error(format!("unexpected end of file in the string {}", temp)));
+        }
+        if !temp.is_empty() {
+            self.tokens.push(Token::new(TokenEdition::ID, temp.to_string()).at(start));
+        }
+        self.lexed = true;
+        Ok(())
+
+    }
+
+    // The cursor stays on the last token when the program ends early, which is where the error is shown
+    fn get_next_token(&mut self) -> Result<&Token, Diagnostic> {
+        if self.current_index + 1 >= self.tokens.len() {
+            return Err(self.end_of_file());
+        }
+        self.current_index += 1;
+        Ok(&self.tokens[self.current_index])
+    }
+
+    // Parsing starts on a token and never moves past the last one, so there always is a current token
+    fn cur_token(&self) -> &Token {
+        &self.tokens[self.current_index]
+    }
+
+    fn peek(&self) -> Result<&Token, Diagnostic> {
+        self.tokens.get(self.current_index + 1).ok_or_else(|| self.end_of_file())
+    }
+
+    fn end_of_file(&self) -> Diagnostic {
+        match self.tokens.last() {
+            Some(token) => self.error(format!("unexpected end of file after {}", token.literal)),
+            None => self.error("unexpected end of file, a program starts with data:"),
+        }
+    }
+
+    // Moves to the next token, which has to be one of the kinds
+    fn expect(&mut self, kinds: &[TokenEdition]) -> Result<&Token, Diagnostic> {
+        self.get_next_token()?;
+        self.expect_current(kinds)
+    }
+
+    // The current token, which has to be one of the kinds -> expected number or vector but found vektor
+    fn expect_current(&self, kinds: &[TokenEdition]) -> Result<&Token, Diagnostic> {
+        let token = self.cur_token();
+        if kinds.contains(&token.kind) {
+            return Ok(token);
+        }
+        let expected: Vec<&str> = kinds.iter().map(|kind| kind.describe()).collect();
+        Err(self.error(format!("expected {} but found {}", expected.join(" or "), token.literal)))
+    }
+
+    // An error found at the token or char the store is at
+    fn error(&self, message: impl Into<String>) -> Diagnostic {
+        Diagnostic::new(message).at(self.position())
+    }
+
+    pub fn program_syntax(&mut self) -> Result<(), Diagnostic> {
+        if self.tokens.is_empty() {
+            return Err(self.end_of_file());
+        }
+        // A program may start with a missing value policy for all of its reads -> missing = drop
+        if self.cur_token().kind == TokenEdition::ID && self.cur_token().literal == "missing" {
+            self.expect(&[TokenEdition::ASSIGN])?;
+            self.program.missing = Some(self.missing_policy()?);
+            self.get_next_token()?;
+        }
+
+        self.expect_current(&[TokenEdition::DATA])?; // Every programs token should begin with Data
+        self.expect(&[TokenEdition::COLON])?; // Followed by a colon
+
+        // Below, the following functions will begin
+        self.data_defs()?;
+        self.input_ops()?;
+        self.process_ops()?;
+        self.output_ops()?;
+
+        if self.verbose {
+            eprintln!("Syntax Analysis Completed");
+        }
+        Ok(())
+    }
+
+    pub fn lex_and_parse_statement(&mut self) -> Result<Option<Statement>, Diagnostic> {
+        // The scanner ends an ID at a newline, so a lone `m` needs one after it
+        if !self.source_code.ends_with('\n') {
+            self.source_code.push('\n');
+        }
+        self.lex_source()?;
+        self.statement_syntax()
+    }
+
+    // Parses the tokens as one statement with the same rules as a whole program
+    // -> x : vector, x = read("f.csv", true, 0), m = mean(x), "text", m, missing = drop
+    pub fn statement_syntax(&mut self) -> Result<Option<Statement>, Diagnostic> {
+        // A section header or a trailing comma, as in a line copied out of a program, is left out
+        if self.tokens.len() >= 2
+            && matches!(
+                self.tokens[0].kind,
+                TokenEdition::DATA | TokenEdition::INPUT | TokenEdition::PROCESS | TokenEdition::OUTPUT
+            )
+            && self.tokens[1].kind == TokenEdition::COLON
+        {
+            self.tokens.drain(..2);
+        }
+        if self.tokens.last().is_some_and(|token| token.kind == TokenEdition::COMMA) {
+            self.tokens.pop();
+        }
+        if self.tokens.first().is_some_and(|token| token.kind == TokenEdition::END) {
+            self.tokens.clear();
+        }
+        if self.tokens.is_empty() {
+            return Ok(None);
+        }
+
+        // The rules start on the token before a statement and look for END after the last output op,
+        // so the statement is put between a colon and an end. as if it were the only one in its section
+        let first = self.tokens[0].position;
+        let last = self.tokens[self.tokens.len() - 1].position;
+        self.tokens.insert(0, Token::new(TokenEdition::COLON, String::from(":")).at(first));
+        self.tokens.push(Token::new(TokenEdition::END, String::from("end")).at(last));
+        self.tokens.push(Token::new(TokenEdition::PERIOD, String::from(".")).at(last));
+        self.current_index = 0;
+
+        let kinds: Vec<TokenEdition> = self.tokens.iter().skip(1).take(3).map(|token| token.kind).collect();
+        let statement = match kinds[..] {
+            [TokenEdition::ID, TokenEdition::ASSIGN, TokenEdition::ID] if self.tokens[1].literal == "missing" => {
+                self.current_index = 2;
+                Statement::Missing(self.missing_policy()?)
+            }
+            [TokenEdition::ID, TokenEdition::COLON, ..] => {
+                self.data_def()?;
+                Statement::Data(self.program.data.last().unwrap().clone())
+            }
+            [TokenEdition::ID, TokenEdition::ASSIGN, TokenEdition::READ | TokenEdition::READTSV | TokenEdition::READJSON] => {
+                self.input_op()?;
+                Statement::Input(self.program.inputs.last().unwrap().clone())
+            }
+            [TokenEdition::ID, TokenEdition::ASSIGN, ..] => {
+                self.process_op()?;
+                Statement::Process(self.program.processes.last().unwrap().clone())
+            }
+            _ => {
+                self.output_op()?;
+                Statement::Output(self.program.outputs.last().unwrap().clone())
+            }
+        };
+
+        if self.get_next_token()?.kind != TokenEdition::END {
+            return Err(self.error(format!("Found {} after the end of the statement", self.cur_token().literal)));
+        }
+        Ok(Some(statement))
+    }
+
+    pub fn tokens(&self) -> &[Token] {
+        &self.tokens
+    }
+
+    // The parsed program, filled in by program_syntax
+    pub fn program(&self) -> &Program {
+        &self.program
+    }
+
+    pub fn file_name(&self) -> &str {
+        &self.file_name
+    }
+
+    pub fn source_code(&self) -> &str {
+        &self.source_code
+    }
+
+    // Where scanning or parsing stopped, which after an error is where the error was found
+    pub fn position(&self) -> Position {
+        if !self.lexed {
+            return self.position;
+        }
+        match self.tokens.get(self.current_index).or(self.tokens.last()) {
+            Some(token) => token.position,
+            None => self.position,
+        }
+    }
+
+    pub fn set_verbose(&mut self, verbose: bool) {
+        self.verbose = verbose;
+    }
+
+    pub fn set_paths(&mut self, paths: PathBase) {
+        self.paths = paths;
+    }
+
+    pub fn set_tables(&mut self, tables: Tables) {
+        self.tables = tables;
+    }
+
+    // The generated program for one of the TARGETS, or the first thing in the program the target cannot express
+    pub fn generate(&self, target: &str) -> Result<String, Diagnostic> {
+        let code = match target {
+            "scheme" => self.scheme_program(),
+            "prolog" => self.prolog_program(),
+            "javascript" => codegen::javascript::program(&self.program, &self.file_name),
+            "sql" => codegen::sql::program(&self.program, &self.tables, &self.file_name)?,
+            "julia" => codegen::julia::program(&self.program, &self.file_name)?,
+            "octave" => codegen::octave::program(&self.program, &self.file_name)?,
+            "rust" => codegen::rust::program(&self.program, &self.file_name),
+            _ => return Err(Diagnostic::new(format!("{} is not a build target", target))),
+        };
+        Ok(code)
+    }
+
+    // The generated program preceded by the runtime of the target, so it runs on its own
+    pub fn generate_standalone(&self, target: &str) -> Result<String, Diagnostic> {
+        match runtime::runtime(target) {
+            Some(runtime) => Ok(format!("{}\n{}", runtime, self.generate(target)?)),
+            None if runtime::embedded(target).is_some() => self.generate(target),
+            None => Err(Diagnostic::new(format!("There is no standalone runtime for {}", target))),
+        }
+    }
+
+    fn scheme_program(&self) -> String {
+        let mut program = format!(
+            "; Processing Input File {}\n; Lexical and Syntax analysis passed\n",
+            self.file_name
+        );
+        for val in &self.scheme_output {
+            program.push_str(val);
+            program.push('\n');
+        }
+        program
+    }
+
+    fn prolog_program(&self) -> String {
+        let mut program = format!(
+            "/* Processing input file {}\n   Lexical and Syntax analysis passed */\n\n  main :-\n",
+            self.file_name
+        );
+        for val in &self.pl_output {
+            program.push('\t');
+            program.push_str(val);
+            program.push('\n');
+        }
+        program
+    }
+
+    // Data defs will call data def, and while there is a comma remaining after the call as the next token, we know there should be another data definition
+    fn data_defs(&mut self) -> Result<(), Diagnostic> {
+        self.data_def()?;
+        while self.get_next_token()?.kind == TokenEdition::COMMA {
+            self.data_def()?;
+        }
+        // at the end, we assume since there was no comma after a datadef, the input section should follow.
+        self.expect_current(&[TokenEdition::INPUT])?;
+        self.expect(&[TokenEdition::COLON])?;
+        Ok(())
+    }
+
+    fn data_def(&mut self) -> Result<(), Diagnostic> {
+        // We should have a token with an ID value
+        let id = self.expect(&[TokenEdition::ID])?.literal.clone();
+        self.id_check(&id)?; // Checking ID Lexically
+        // then a colon
+        self.expect(&[TokenEdition::COLON])?;
+        // Then either a number of a vector
+        let kind = self.expect(&[TokenEdition::NUMBER, TokenEdition::VECTOR])?.kind;
+        self.program.data.push(DataDef { id, kind });
+        Ok(())
+    }
+
+    fn input_ops(&mut self) -> Result<(), Diagnostic> {
+        self.input_op()?;
+        while self.get_next_token()?.kind == TokenEdition::COMMA {
+            self.input_op()?;
+        }
+
+        self.expect_current(&[TokenEdition::PROCESS])?;
+        self.expect(&[TokenEdition::COLON])?;
+        Ok(())
+    }
+
+    fn input_op(&mut self) -> Result<(), Diagnostic> {
+        // When building an input op, it begins with either define or one of the prolog loaders
+        // This function will parse through the input op, check the syntax, and build the string that will be placed in a vector
+        let mut scheme_builder = String::from("(define ");
+        let mut prolog_builder = String::from("");
+
+        let pl_id = self.expect(&[TokenEdition::ID])?.literal.clone(); // saving ID for prolog
+        self.id_check(&pl_id)?;
+        scheme_builder.push_str(&self.names.scheme_identifier(&pl_id)); // Scheme output adds ID
+
+        self.expect(&[TokenEdition::ASSIGN])?;
+        let reader_kind = self.expect(&[TokenEdition::READ, TokenEdition::READTSV, TokenEdition::READJSON])?.kind;
+
+        // Each reader has its own loader in the generated code
+        if reader_kind == TokenEdition::READ {
+            scheme_builder.push_str(" (read-csv ");
+            prolog_builder.push_str("load_data_column(");
+        } else if reader_kind == TokenEdition::READTSV {
+            scheme_builder.push_str(" (read-tsv ");
+            prolog_builder.push_str("load_tsv_column(");
+        } else {
+            scheme_builder.push_str(" (read-json ");
+            prolog_builder.push_str("load_json_values(");
+        }
+
+        self.expect(&[TokenEdition::LPAREN])?;
+        let literal = self.expect(&[TokenEdition::STRING])?.literal.clone();
+
+        // The path is resolved once here, so every language and the interpreter read the same file
+        let name = unescape(&self.strip_quotes(literal));
+        let file = self.resolve_path(&name)?;
+        scheme_builder.push_str(&quote::scheme_string(&file));
+        prolog_builder.push_str(&quote::prolog_atom(&file));
+
+        self.expect(&[TokenEdition::COMMA])?;
+
+        prolog_builder.push_str(&self.cur_token().literal); // Appending commas to prolog
+
+        let source = if reader_kind == TokenEdition::READJSON {
+            // read_json takes a path into the document instead of a header flag and a column -> "$.items[*].price"
+            let literal = self.expect(&[TokenEdition::STRING])?.literal.clone();
+            let path = self.strip_quotes(literal);
+            scheme_builder.push(' ');
+            scheme_builder.push_str(&quote::scheme_string(&path));
+            prolog_builder.push(' ');
+            prolog_builder.push_str(&quote::prolog_string(&path));
+            InputSource::Json { path }
+        } else {
+            self.expect(&[TokenEdition::TRUE, TokenEdition::FALSE])?;
+            if self.cur_token().kind == TokenEdition::TRUE {
+                scheme_builder.push_str(" #t "); // true value becomes #t
+                prolog_builder.push(' ');
+                prolog_builder.push_str(&self.cur_token().literal);
+            } else if self.cur_token().kind == TokenEdition::FALSE {
+                scheme_builder.push_str(" #f "); // false value becomes #f 
+                prolog_builder.push(' ');
+                prolog_builder.push_str(&self.cur_token().literal);
+                // prolog in both cases remains the same
+            }
+            let header = self.cur_token().kind == TokenEdition::TRUE;
+
+            self.expect(&[TokenEdition::COMMA])?;
+            prolog_builder.push_str(&self.cur_token().literal);
+
+            self.expect(&[TokenEdition::NUM])?;
+
+            prolog_builder.push(' '); // Adding Spaces for Scheme output if our number is found. . .
+            prolog_builder.push_str(&self.cur_token().literal);
+            scheme_builder.push_str(&self.cur_token().literal);
+            let column = self.number()?;
+
+            if reader_kind == TokenEdition::READTSV {
+                InputSource::Tsv { header, column }
+            } else {
+                InputSource::Csv { header, column }
+            }
+        };
+
+        // Any further comma means named options follow -> delimiter = "\t", quote = "'"
+        let mut options = ReadOptions::default();
+        while self.get_next_token()?.kind == TokenEdition::COMMA {
+            self.read_option(&mut options)?;
+        }
+        if reader_kind != TokenEdition::READ && options.delimiter.is_some() {
+            return Err(self.error(format!("{} does not take a delimiter option", reader_kind.describe())));
+        }
+        if reader_kind == TokenEdition::READJSON && options.quote.is_some() {
+            return Err(self.error(format!("{} does not take a quote option", reader_kind.describe())));
+        }
+
+        // The generated code gets the policy that applies to this read, even when it was given for the whole program
+        let mut emitted = options.clone();
+        if emitted.missing.is_none() {
+            emitted.missing = self.program.missing;
+        }
+
+        if !emitted.is_empty() {
+            // Scheme receives the options as an association list, Prolog as an option list before the result variable
+            scheme_builder.push_str(" '(");
+            scheme_builder.push_str(&self.scheme_read_options(&emitted).join(" "));
+            scheme_builder.push(')');
+
+            prolog_builder.push_str(", [");
+            prolog_builder.push_str(&self.prolog_read_options(&emitted).join(", "));
+            prolog_builder.push(']');
+        }
+
+        self.expect_current(&[TokenEdition::RPAREN])?;
+        prolog_builder.push_str(", ");
+        prolog_builder.push_str(&self.names.prolog_data(&pl_id)); // Adding the variable the vector is read into
+        prolog_builder.push_str(&self.cur_token().literal); // then adding our current parentheses to the string
+
+        scheme_builder.push_str("))");
+        prolog_builder.push(',');
+
+        self.scheme_output.push(scheme_builder);
+        self.pl_output.push(prolog_builder);
+        // Pushing both strings to their respective vectors...
+
+        self.program.inputs.push(InputOp {
+            id: pl_id,
+            file,
+            name,
+            source,
+            options,
+        });
+        Ok(())
+    }
+
+    // A single named option of read(...), an ID followed by = and a string -> delimiter = ";"
+    // rows takes a range instead -> rows = 10..500 or rows = 10.., and skip_missing a truthy value
+    fn read_option(&mut self, options: &mut ReadOptions) -> Result<(), Diagnostic> {
+        let name = self.expect(&[TokenEdition::ID])?.literal.clone();
+        self.expect(&[TokenEdition::ASSIGN])?;
+
+        if name == "rows" {
+            if options.rows.is_some() {
+                return Err(self.error("The read option rows was given more than once"));
+            }
+            self.expect(&[TokenEdition::NUM])?;
+            let start = self.number()?;
+            self.expect(&[TokenEdition::PERIOD])?;
+            self.expect(&[TokenEdition::PERIOD])?;
+            let mut end = None;
+            if self.peek()?.kind == TokenEdition::NUM {
+                self.get_next_token()?;
+                end = Some(self.number()?);
+            }
+            if let Some(end) = end {
+                if start > end {
+                    return Err(self.error(format!("The rows range {}..{} ends before it starts", start, end)));
+                }
+            }
+            options.rows = Some(RowRange { start, end });
+            return Ok(());
+        }
+        if name == "missing" {
+            if options.missing.is_some() {
+                return Err(self.error("The read option missing was given more than once"));
+            }
+            options.missing = Some(self.missing_policy()?);
+            return Ok(());
+        }
+        if name == "skip_missing" {
+            options.skip_missing = self.expect(&[TokenEdition::TRUE, TokenEdition::FALSE])?.kind == TokenEdition::TRUE;
+            return Ok(());
+        }
+
+        let literal = self.expect(&[TokenEdition::STRING])?.literal.clone();
+        let value = self.strip_quotes(literal);
+
+        let slot = match name.as_str() {
+            "delimiter" => &mut options.delimiter,
+            "quote" => &mut options.quote,
+            "encoding" => &mut options.encoding,
+            _ => {
+                return Err(self.error(format!(
+                    "{} is not a read option, expected delimiter, quote, encoding, rows, skip_missing or missing",
+                    name
+                )))
+            }
+        };
+        if slot.is_some() {
+            return Err(self.error(format!("The read option {} was given more than once", name)));
+        }
+        *slot = Some(value);
+        Ok(())
+    }
+
+    // The value of a missing option, one of drop, zero or error
+    fn missing_policy(&mut self) -> Result<MissingPolicy, Diagnostic> {
+        let policy = self.expect(&[TokenEdition::ID])?.literal.clone();
+        match policy.as_str() {
+            "drop" => Ok(MissingPolicy::Drop),
+            "zero" => Ok(MissingPolicy::Zero),
+            "error" => Ok(MissingPolicy::Error),
+            other => Err(self.error(format!("{} is not a missing value policy, expected drop, zero or error", other))),
+        }
+    }
+
+    // The NUM token the store is at as a column or row number
+    fn number(&self) -> Result<usize, Diagnostic> {
+        let literal = &self.cur_token().literal;
+        literal.parse::<usize>().map_err(|_| self.error(format!("{} is too large for a row or column number", literal)))
+    }
+
+    // '((delimiter . ";") (rows 10 500) (skip-missing . #t) (missing . drop))
+    fn scheme_read_options(&self, options: &ReadOptions) -> Vec<String> {
+        let mut opts: Vec<String> = options
+            .entries()
+            .iter()
+            .map(|(name, value)| format!("({} . {})", name, quote::scheme_string(&unescape(value))))
+            .collect();
+        if let Some(rows) = &options.rows {
+            match rows.end {
+                Some(end) => opts.push(format!("(rows {} {})", rows.start, end)),
+                None => opts.push(format!("(rows {} #f)", rows.start)),
+            }
+        }
+        if options.skip_missing {
+            opts.push("(skip-missing . #t)".to_string());
+        }
+        if let Some(missing) = options.missing {
+            opts.push(format!("(missing . {})", missing.name()));
+        }
+        opts
+    }
+
+    // [delimiter(";"), rows(10, 500), skip_missing(true), missing(drop)]
+    fn prolog_read_options(&self, options: &ReadOptions) -> Vec<String> {
+        let mut opts: Vec<String> = options
+            .entries()
+            .iter()
+            .map(|(name, value)| format!("{}({})", name, quote::prolog_string(&unescape(value))))
+            .collect();
+        if let Some(rows) = &options.rows {
+            match rows.end {
+                Some(end) => opts.push(format!("rows({}, {})", rows.start, end)),
+                None => opts.push(format!("rows({}, inf)", rows.start)),
+            }
+        }
+        if options.skip_missing {
+            opts.push("skip_missing(true)".to_string());
+        }
+        if let Some(missing) = options.missing {
+            opts.push(format!("missing({})", missing.name()));
+        }
+        opts
+    }
+
+
+    // This function will call process ops and will continue until there is no comma remaining
+    fn process_ops(&mut self) -> Result<(), Diagnostic> {
+        self.process_op()?;
+        while self.get_next_token()?.kind == TokenEdition::COMMA {
+            self.process_op()?;
+        }
+
+        // at the end, assuming there is no comma, we know we have reached the end of the process ops and should be at the output section
+        self.expect_current(&[TokenEdition::OUTPUT])?;
+        self.expect(&[TokenEdition::COLON])?;
+        Ok(())
+    }
+
+
+    // This function handles two flows, some functions can take 1 or 2 parameters, and based on that assertions are made for the next token
+    fn process_op(&mut self) -> Result<(), Diagnostic> {
+        let mut scheme_builder = String::from("(define ");
+        let mut prolog_builder = String::from("");
+
+        let pl_id = self.expect(&[TokenEdition::ID])?.literal.clone();
+        self.id_check(&pl_id)?; // Checking our string with a regex
+        scheme_builder.push_str(&self.names.scheme_identifier(&pl_id));
+
+        self.expect(&[TokenEdition::ASSIGN])?;
+        let function_kind = self
+            .expect(&[
+                TokenEdition::CORRELATION,
+                TokenEdition::MEAN,
+                TokenEdition::REGRESSIONA,
+                TokenEdition::REGRESSIONB,
+                TokenEdition::STDDEV,
+            ])?
+            .kind;
+
+        scheme_builder.push_str(" (");
+        scheme_builder.push_str(&self.cur_token().literal);
+        prolog_builder.push_str(&self.cur_token().literal); // Appending our function type to our source code...
+
+        self.expect(&[TokenEdition::LPAREN])?;
+        prolog_builder.push_str(&self.cur_token().literal);
+
+        let mut args = vec![self.expect(&[TokenEdition::ID])?.literal.clone()];
+
+        // pushing id in scheme
+        scheme_builder.push(' ');
+        scheme_builder.push_str(&self.names.scheme_identifier(&args[0]));
+        // Pro log pushes ID
+        prolog_builder.push_str(&self.names.prolog_variable(&args[0]));
+        prolog_builder.push_str(", ");
+
+        //  println!("{:?}", self.cur_token().kind);
+        if function_kind == TokenEdition::CORRELATION
+            || function_kind == TokenEdition::REGRESSIONA
+            || function_kind == TokenEdition::REGRESSIONB
+        {
+            self.expect(&[TokenEdition::COMMA])?;
+            args.push(self.expect(&[TokenEdition::ID])?.literal.clone());
+
+            scheme_builder.push(' ');
+            scheme_builder.push_str(&self.names.scheme_identifier(&args[1]));
+
+            prolog_builder.push_str(&self.names.prolog_variable(&args[1]));
+            prolog_builder.push_str(", ");
+        }
+
+        if (function_kind == TokenEdition::MEAN || function_kind == TokenEdition::STDDEV)
+            && self.get_next_token()?.kind != TokenEdition::RPAREN
+        {
+            return Err(self.error(format!("{} can only take in one parameter", function_kind.describe())));
+        }
+
+        prolog_builder.push_str(&self.names.prolog_assignment(&pl_id));
+
+        if function_kind == TokenEdition::CORRELATION
+            || function_kind == TokenEdition::REGRESSIONA
+            || function_kind == TokenEdition::REGRESSIONB
+        {
+            self.expect(&[TokenEdition::RPAREN])?;
+        }
+
+        scheme_builder.push_str("))");
+        prolog_builder.push_str("),");
+
+        self.scheme_output.push(scheme_builder);
+        self.pl_output.push(prolog_builder);
+
+        self.program.processes.push(ProcessOp {
+            id: pl_id,
+            function: function_kind,
+            args,
+        });
+        Ok(())
+    }
+
+    fn output_ops(&mut self) -> Result<(), Diagnostic> {
+        // We call the output opp function, if a comma is the next token, we know another output op follows, we continue this logic until no comma remains
+        self.output_op()?;
+        while self.get_next_token()?.kind == TokenEdition::COMMA {
+            self.output_op()?;
+        }
+
+        // After there is no comma, we expect there to be an end token, signifying the end of the file.
+        self.expect_current(&[TokenEdition::END])?;
+        self.expect(&[TokenEdition::PERIOD])?;
+        Ok(())
+    }
+
+
+    // This function will build one line of output, for an output operation in scheme and prolog
+    fn output_op(&mut self) -> Result<(), Diagnostic> {
+        if self.peek()?.kind == TokenEdition::WRITE {
+            return self.write_op();
+        }
+
+        let mut scheme_builder = String::from("(display ");
+        let mut prolog_builder = String::from("writeln(");
+
+        self.expect(&[TokenEdition::STRING, TokenEdition::ID])?;
+
+        if self.cur_token().kind == TokenEdition::ID {
+            let id = self.cur_token().literal.clone();
+            scheme_builder.push_str(&self.names.scheme_identifier(&id));
+            prolog_builder.push_str(&self.names.prolog_variable(&id));
+            self.program.outputs.push(OutputOp::Value(id));
+        } else if self.cur_token().kind == TokenEdition::STRING {
+            let text = self.strip_quotes(self.cur_token().literal.clone());
+            scheme_builder.push_str(&quote::scheme_string(&unescape(&text)));
+            prolog_builder.push_str(&quote::prolog_string(&unescape(&text)));
+            self.program.outputs.push(OutputOp::Text(text));
+        }
+        scheme_builder.push(')');
+
+        if self.peek()?.kind == TokenEdition::END {
+            prolog_builder.push_str(").");
+        } else {
+            prolog_builder.push_str("),");
+        }
+
+        // Every displayed value ends its line, as writeln does in Prolog. A write has no line on the screen
+        self.scheme_output.push(scheme_builder);
+        self.scheme_output.push("(newline)".to_string());
+        self.pl_output.push(prolog_builder);
+        Ok(())
+    }
+
+    // write("results.csv", a, b, r) saves the named values to a file instead of displaying them
+    fn write_op(&mut self) -> Result<(), Diagnostic> {
+        let mut scheme_builder = String::from("(write-results ");
+        let mut prolog_builder = String::from("write_results(");
+
+        self.expect(&[TokenEdition::WRITE])?;
+        self.expect(&[TokenEdition::LPAREN])?;
+        let literal = self.expect(&[TokenEdition::STRING])?.literal.clone();
+
+        let file = self.resolve_path(&unescape(&self.strip_quotes(literal)))?;
+        scheme_builder.push_str(&quote::scheme_string(&file));
+        prolog_builder.push_str(&quote::prolog_atom(&file));
+
+        // Every ID after the file name is saved under its own name
+        let mut ids = Vec::new();
+        while self.get_next_token()?.kind == TokenEdition::COMMA {
+            let id = self.expect(&[TokenEdition::ID])?.literal.clone();
+            self.id_check(&id)?;
+            ids.push(id);
+        }
+        if ids.is_empty() {
+            return Err(self.error("write needs at least one value to save after the file name"));
+        }
+        self.expect_current(&[TokenEdition::RPAREN])?;
+
+        // Scheme gets an association list of names and values, Prolog a list of Name-Value pairs
+        scheme_builder.push_str(" (list");
+        for id in &ids {
+            let name = self.names.scheme_identifier(id);
+            scheme_builder.push_str(&format!(" (cons {} {})", quote::scheme_string(id), name));
+        }
+        scheme_builder.push_str("))");
+
+        let pl_pairs: Vec<String> = ids
+            .iter()
+            .map(|id| format!("{}-{}", quote::prolog_atom(id), self.names.prolog_variable(id)))
+            .collect();
+        prolog_builder.push_str(", [");
+        prolog_builder.push_str(&pl_pairs.join(", "));
+        prolog_builder.push(']');
+
+        if self.peek()?.kind == TokenEdition::END {
+            prolog_builder.push_str(").");
+        } else {
+            prolog_builder.push_str("),");
+        }
+
+        self.scheme_output.push(scheme_builder);
+        self.pl_output.push(prolog_builder);
+        self.program.outputs.push(OutputOp::Write { file, ids });
+        Ok(())
+    }
+
+    fn id_check(&self, value: &str) -> Result<(), Diagnostic> {
+        // an ID checker that makes sure an ID is valid
+        let pattern = r"[a-z]+";
+        let re = Regex::new(pattern).unwrap();
+        if !re.is_match(value) {
+            return Err(self.error(format!("{} is not a valid ID, an ID needs a lowercase letter", value)));
+        }
+        Ok(())
+    }
+
+    fn string_check(&self, value: &str) -> Result<(), Diagnostic> {
+        // Strings hold file names, JSON paths and read options, so letters of both cases, digits and their punctuation
+        let pattern = r#"[^A-Za-z.0-9 =_/\\;:,'"|$*\[\]\t-]"#;
+        let re = Regex::new(pattern).unwrap();
+
+        if let Some(invalid) = re.find(value) {
+            return Err(self.error(format!("invalid character '{}' in string", invalid.as_str())));
+        }
+        Ok(())
+    }
+
+    // The file name of a read or write, already unescaped like any other string, resolved against the
+    // PathBase of the store, so every target and the interpreter open the same file
+    fn resolve_path(&self, file: &str) -> Result<String, Diagnostic> {
+        paths::resolve(&self.paths, &self.file_name, file).map_err(|message| self.error(message))
+    }
+
+    fn strip_quotes(&self, mut val: String) -> String {
+        // "file.csv" -> file.csv
+        val.pop();
+        val.remove(0);
+        val
+    }
+
+}
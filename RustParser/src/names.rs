@@ -0,0 +1,254 @@
+use std::collections::{HashMap, HashSet};
+
+// Words a DA identifier cannot be called in Scheme: R7RS syntax, the procedures the generated code
+// and the runtime call, and every name the runtime defines at the top level
+const SCHEME_RESERVED: [&str; 128] = [
+    "and", "begin", "case", "cond", "define", "delay", "do", "else", "guard", "if", "import", "include",
+    "lambda", "let", "let*", "let-values", "letrec", "or", "parameterize", "quasiquote", "quote", "set!",
+    "unless", "unquote", "when", "=>",
+    "abs", "append", "apply", "assoc", "assq", "assv", "caar", "cadr", "call-with-input-file",
+    "call-with-output-file", "car", "cdar", "cddr", "cdr", "ceiling", "char-whitespace?", "char=?", "cons",
+    "delete-file", "denominator", "display", "eof-object?", "eq?", "error", "exact", "exact-integer?", "exit",
+    "expt", "file-exists?", "floor", "gcd", "inexact", "integer->char", "lcm", "length", "list",
+    "list->string", "list-ref", "list?", "map", "max", "member", "memq", "memv", "min", "modulo", "nan?",
+    "newline", "not", "null?", "number->string", "number?", "numerator", "pair?", "quotient", "read-string",
+    "remainder", "reverse", "round", "sqrt", "string", "string->number", "string-append", "string-downcase",
+    "string-length", "string-ref", "string=?", "substring", "truncate", "values", "vector", "write",
+    "write-string",
+    "correlation", "da-char-option", "da-complete-pairs", "da-in-rows?", "da-json-array?", "da-json-object?",
+    "da-json-path-steps", "da-json-step", "da-missing-value", "da-missing?", "da-option", "da-parse-json",
+    "da-present", "da-read-column", "da-read-file", "da-split-records", "da-suffix?", "da-sums-of-squares",
+    "da-trim", "da-write-csv", "da-write-json", "mean", "read-csv", "read-json", "read-tsv", "regressiona",
+    "regressionb", "stddev", "write-results",
+];
+
+// JavaScript keywords and globals, plus the runtime functions and io object the generated main uses
+const JAVASCRIPT_RESERVED: [&str; 58] = [
+    "await", "break", "case", "catch", "class", "const", "continue", "debugger", "default", "delete", "do",
+    "else", "enum", "export", "extends", "false", "finally", "for", "function", "if", "implements", "import",
+    "in", "instanceof", "interface", "let", "new", "null", "package", "private", "protected", "public",
+    "return", "static", "super", "switch", "this", "throw", "true", "try", "typeof", "var", "void", "while",
+    "with", "yield", "arguments", "eval", "undefined", "NaN", "Infinity", "io", "readCsv", "readTsv",
+    "readJson", "writeResults", "show", "main",
+];
+
+// Julia keywords and the names the runtime of the generated script defines or calls
+const JULIA_RESERVED: [&str; 79] = [
+    "abstract", "baremodule", "begin", "break", "catch", "const", "continue", "do", "else", "elseif", "end",
+    "export", "false", "finally", "for", "function", "global", "if", "import", "in", "isa", "let", "local",
+    "macro", "module", "mutable", "primitive", "quote", "return", "struct", "true", "try", "type", "using",
+    "where", "while", "missing", "nothing", "pi", "Inf", "NaN", "CSV", "Statistics", "println", "cor", "std",
+    "present", "complete_pairs", "slope", "intercept", "read_column", "read_tsv_column", "write_results",
+    "mean", "var", "cov", "DA_MISSING", "da_missing_value", "da_single_char", "da_decode", "da_json", "filter",
+    "isnan", "length", "error", "repr", "join", "string", "first", "last", "map", "lowercase", "strip",
+    "tryparse", "only", "maximum", "enumerate", "endswith", "ismissing",
+];
+
+// Octave and MATLAB keywords, the functions the generated script calls and its own temporaries
+const OCTAVE_RESERVED: [&str; 72] = [
+    "break", "case", "catch", "continue", "do", "else", "elseif", "end", "end_try_catch",
+    "end_unwind_protect", "endfor", "endfunction", "endif", "endswitch", "endwhile", "for", "function",
+    "global", "if", "otherwise", "parfor", "persistent", "return", "switch", "try", "until",
+    "unwind_protect", "unwind_protect_cleanup", "while", "ans", "any", "cell", "cellfun", "char", "corrcoef",
+    "disp", "error", "fclose", "fileread", "fopen", "fprintf", "isempty", "isnan", "jsonencode", "max",
+    "min", "numel", "polyfit", "regexp", "regexptranslate", "sprintf", "std", "str2double", "strcmpi",
+    "strjoin", "strtrim", "struct", "NaN", "Inf", "mean", "da_keep", "da_fit", "da_values", "da_file",
+    "da_row", "da_col", "da_cells", "da_value", "da_lines", "da_delimiter", "da_cell", "da_r",
+];
+
+// Rust keywords, patterns a let would match instead of binding and the names the runtime of the
+// generated main.rs defines
+const RUST_RESERVED: [&str; 83] = [
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern", "false",
+    "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref", "return",
+    "self", "Self", "static", "struct", "super", "trait", "true", "type", "unsafe", "use", "where", "while",
+    "abstract", "become", "box", "do", "final", "gen", "macro", "override", "priv", "try", "typeof",
+    "unsized", "virtual", "yield", "union", "None", "Some", "Ok", "Err", "Missing", "ReadOptions", "Json",
+    "JsonParser", "Value", "BTreeMap", "fs", "process", "std", "decode", "single_char", "split_records",
+    "is_missing", "missing_value", "read_csv", "read_tsv", "read_json", "select", "json_number", "present",
+    "complete_pairs", "sums_of_squares", "json_string", "write_results", "main", "run",
+];
+
+// The names DA identifiers get in the generated code. Each identifier keeps one name per target,
+// valid there, clear of its reserved words and different from the name of every other identifier.
+// Prolog binds a variable once, so there an identifier that is assigned again gets a new name
+// -> xvalues = read(...) is Data0 in Prolog, a = mean(...) is A, and list is list_2 in Scheme
+#[derive(Debug)]
+pub struct Names {
+    prolog: Namespace,
+    scheme: Namespace,
+    javascript: Namespace,
+    rust: Namespace,
+    julia: Namespace,
+    octave: Namespace,
+    data_count: usize, // Prolog variables that hold a vector read by an input op are Data0, Data1, ...
+}
+
+#[derive(Debug, Default)]
+struct Namespace {
+    names: HashMap<String, String>,
+    taken: HashSet<String>,
+}
+
+impl Namespace {
+    fn reserve(&mut self, words: &[&str]) {
+        self.taken.extend(words.iter().map(|word| word.to_string()));
+    }
+
+    // The name already given to the identifier, or the wanted one made unique with a number
+    fn name(&mut self, id: &str, wanted: impl FnOnce() -> String) -> String {
+        if let Some(name) = self.names.get(id) {
+            return name.clone();
+        }
+        let base = wanted();
+        let mut name = base.clone();
+        let mut n = 2;
+        while self.taken.contains(&name) {
+            name = format!("{}_{}", base, n);
+            n += 1;
+        }
+        self.taken.insert(name.clone());
+        self.names.insert(id.to_string(), name.clone());
+        name
+    }
+
+    // A new name for an identifier that is assigned again, which later uses then get
+    fn rename(&mut self, id: &str, wanted: impl FnOnce() -> String) -> String {
+        self.names.remove(id);
+        self.name(id, wanted)
+    }
+}
+
+impl Default for Names {
+    fn default() -> Self {
+        let mut scheme = Namespace::default();
+        scheme.reserve(&SCHEME_RESERVED);
+        let mut javascript = Namespace::default();
+        javascript.reserve(&JAVASCRIPT_RESERVED);
+        let mut rust = Namespace::default();
+        rust.reserve(&RUST_RESERVED);
+        let mut julia = Namespace::default();
+        julia.reserve(&JULIA_RESERVED);
+        let mut octave = Namespace::default();
+        octave.reserve(&OCTAVE_RESERVED);
+        Self { prolog: Namespace::default(), scheme, javascript, rust, julia, octave, data_count: 0 }
+    }
+}
+
+impl Names {
+    // The Prolog variable an input op reads its vector into, a new one for every read since a
+    // Prolog variable is bound only once -> Data0, and Data1 when the same ID is read again
+    pub fn prolog_data(&mut self, id: &str) -> String {
+        let count = &mut self.data_count;
+        self.prolog.rename(id, || {
+            let name = format!("Data{}", count);
+            *count += 1;
+            name
+        })
+    }
+
+    // The Prolog variable a process op assigns, a new one each time -> a is A, then A_2
+    pub fn prolog_assignment(&mut self, id: &str) -> String {
+        self.prolog.rename(id, || prolog_name(id))
+    }
+
+    // The Prolog variable of an identifier where it is used, the one it was last assigned to
+    pub fn prolog_variable(&mut self, id: &str) -> String {
+        self.prolog.name(id, || prolog_name(id))
+    }
+
+    pub fn scheme_identifier(&mut self, id: &str) -> String {
+        self.scheme.name(id, || id.to_string())
+    }
+
+    pub fn javascript_identifier(&mut self, id: &str) -> String {
+        self.javascript.name(id, || id.to_string())
+    }
+
+    pub fn rust_identifier(&mut self, id: &str) -> String {
+        self.rust.name(id, || id.to_string())
+    }
+
+    pub fn julia_identifier(&mut self, id: &str) -> String {
+        self.julia.name(id, || id.to_string())
+    }
+
+    // Octave names have to start with a letter
+    pub fn octave_identifier(&mut self, id: &str) -> String {
+        self.octave.name(id, || {
+            if id.starts_with(|c: char| c.is_ascii_alphabetic()) {
+                id.to_string()
+            } else {
+                format!("v{}", id)
+            }
+        })
+    }
+}
+
+// A Prolog variable for an identifier, its name with a capital -> a is A
+fn prolog_name(id: &str) -> String {
+    let mut chars = id.chars();
+    match chars.next() {
+        Some(first) if first.is_ascii_lowercase() => first.to_ascii_uppercase().to_string() + chars.as_str(),
+        // A variable has to start with a capital letter, _ would make it anonymous
+        _ => format!("V{}", id),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reserved_words_get_a_number() {
+        let mut names = Names::default();
+        assert_eq!(names.scheme_identifier("list"), "list_2");
+        assert_eq!(names.javascript_identifier("main"), "main_2");
+        assert_eq!(names.rust_identifier("fn"), "fn_2");
+        assert_eq!(names.julia_identifier("std"), "std_2");
+        assert_eq!(names.octave_identifier("mean"), "mean_2");
+        assert_eq!(names.scheme_identifier("total"), "total");
+    }
+
+    #[test]
+    fn scheme_keeps_clear_of_the_runtime() {
+        let mut names = Names::default();
+        assert_eq!(names.scheme_identifier("sqrt"), "sqrt_2");
+        assert_eq!(names.scheme_identifier("map"), "map_2");
+        assert_eq!(names.scheme_identifier("substring"), "substring_2");
+        // Every name the runtime defines at the top level
+        for line in crate::runtime::runtime("scheme").unwrap().lines() {
+            if let Some(rest) = line.strip_prefix("(define ") {
+                let name = rest.trim_start_matches('(').split([' ', ')']).next().unwrap();
+                assert!(SCHEME_RESERVED.contains(&name), "{} is not reserved", name);
+            }
+        }
+    }
+
+    #[test]
+    fn an_identifier_keeps_its_name() {
+        let mut names = Names::default();
+        assert_eq!(names.scheme_identifier("list"), "list_2");
+        assert_eq!(names.scheme_identifier("list_2"), "list_2_2");
+        assert_eq!(names.scheme_identifier("list"), "list_2");
+    }
+
+    #[test]
+    fn octave_names_start_with_a_letter() {
+        let mut names = Names::default();
+        assert_eq!(names.octave_identifier("_x"), "v_x");
+    }
+
+    #[test]
+    fn prolog_variables_are_bound_once() {
+        let mut names = Names::default();
+        assert_eq!(names.prolog_data("x"), "Data0");
+        assert_eq!(names.prolog_variable("x"), "Data0");
+        assert_eq!(names.prolog_data("x"), "Data1");
+        assert_eq!(names.prolog_variable("x"), "Data1");
+        assert_eq!(names.prolog_assignment("a"), "A");
+        assert_eq!(names.prolog_assignment("a"), "A_2");
+        assert_eq!(names.prolog_variable("a"), "A_2");
+        assert_eq!(names.prolog_assignment("_b"), "V_b");
+    }
+}
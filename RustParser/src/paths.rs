@@ -0,0 +1,175 @@
+use std::env;
+use std::path::{Component, Path, PathBuf};
+
+// What the relative file names in read(...) and write(...) are relative to
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum PathBase {
+    #[default]
+    CurrentDir, // The directory the program runs in, the names are kept as written
+    Source,     // The directory of the DA file -> data/x.csv for read("x.csv") in data/prog.da
+    DataDir(PathBuf), // A root directory that no file name may lead out of
+}
+
+// The file name the generated code and the interpreter use for a name written in the DA file
+// source_file is the DA file itself, which only matters for PathBase::Source
+pub fn resolve(base: &PathBase, source_file: &str, file: &str) -> Result<String, String> {
+    let path = Path::new(file);
+    match base {
+        PathBase::CurrentDir => Ok(file.to_string()),
+        PathBase::Source if path.is_absolute() => Ok(file.to_string()),
+        PathBase::Source => {
+            let dir = Path::new(source_file).parent().unwrap_or(Path::new(""));
+            Ok(display(&normalize(&dir.join(path))))
+        }
+        PathBase::DataDir(root) => {
+            let resolved = normalize(&root.join(path));
+            // Both sides are compared where they really are, so a root of . still catches ../x.csv
+            // and a symbolic link inside the root cannot lead out of it
+            if !physical(&resolved).starts_with(physical(root)) {
+                return Err(format!("{} is outside the data directory {}", file, root.display()));
+            }
+            Ok(display(&resolved))
+        }
+    }
+}
+
+// The absolute path with the symbolic links of its existing part followed. The rest, such as the
+// file a write is about to create, cannot be a link yet and is only normalized
+fn physical(path: &Path) -> PathBuf {
+    let path = env::current_dir().unwrap_or_default().join(path);
+    let components: Vec<Component> = path.components().collect();
+    for existing in (1..=components.len()).rev() {
+        let prefix: PathBuf = components[..existing].iter().collect();
+        if let Ok(real) = prefix.canonicalize() {
+            let rest: PathBuf = components[existing..].iter().collect();
+            return normalize(&real.join(rest));
+        }
+    }
+    normalize(&path)
+}
+
+// Takes out . and folds .. into the directory before it, without looking at the file system
+fn normalize(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => match out.components().next_back() {
+                Some(Component::Normal(_)) => {
+                    out.pop();
+                }
+                // Nothing is above the root, and a leading .. has to stay
+                Some(Component::RootDir) | Some(Component::Prefix(_)) => {}
+                _ => out.push(".."),
+            },
+            other => out.push(other),
+        }
+    }
+    out
+}
+
+fn display(path: &Path) -> String {
+    if path.as_os_str().is_empty() {
+        String::from(".")
+    } else {
+        path.to_string_lossy().into_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    // A fresh directory under the system temp dir with data/x.csv and outside/secret.csv in it
+    fn scratch(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("da-paths-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("data")).unwrap();
+        fs::create_dir_all(dir.join("outside")).unwrap();
+        fs::write(dir.join("data").join("x.csv"), "x\n1\n").unwrap();
+        fs::write(dir.join("outside").join("secret.csv"), "x\n2\n").unwrap();
+        dir
+    }
+
+    #[test]
+    fn current_dir_keeps_the_name() {
+        assert_eq!(resolve(&PathBase::CurrentDir, "prog.da", "../x.csv").unwrap(), "../x.csv");
+    }
+
+    #[test]
+    fn source_is_relative_to_the_program() {
+        let base = PathBase::Source;
+        assert_eq!(resolve(&base, "analyses/prog.da", "x.csv").unwrap(), "analyses/x.csv");
+        assert_eq!(resolve(&base, "analyses/prog.da", "../data/x.csv").unwrap(), "data/x.csv");
+        assert_eq!(resolve(&base, "prog.da", "./x.csv").unwrap(), "x.csv");
+        assert_eq!(resolve(&base, "analyses/prog.da", "/srv/x.csv").unwrap(), "/srv/x.csv");
+    }
+
+    #[test]
+    fn data_dir_resolves_inside() {
+        let dir = scratch("inside");
+        let base = PathBase::DataDir(dir.join("data"));
+        let expected = dir.join("data").join("x.csv").to_string_lossy().into_owned();
+        assert_eq!(resolve(&base, "prog.da", "x.csv").unwrap(), expected);
+        assert_eq!(resolve(&base, "prog.da", "sub/../x.csv").unwrap(), expected);
+        // A file that does not exist yet, as for a write, is fine as long as it stays inside
+        assert!(resolve(&base, "prog.da", "new/results.csv").is_ok());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn data_dir_rejects_parent_escapes() {
+        let dir = scratch("parent");
+        let base = PathBase::DataDir(dir.join("data"));
+        assert!(resolve(&base, "prog.da", "../outside/secret.csv").is_err());
+        assert!(resolve(&base, "prog.da", "sub/../../outside/secret.csv").is_err());
+        assert!(resolve(&base, "prog.da", "..").is_err());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn data_dir_rejects_absolute_paths() {
+        let dir = scratch("absolute");
+        let base = PathBase::DataDir(dir.join("data"));
+        let secret = dir.join("outside").join("secret.csv");
+        assert!(resolve(&base, "prog.da", &secret.to_string_lossy()).is_err());
+        // An absolute name that points into the root is still inside it
+        let inside = dir.join("data").join("x.csv");
+        assert!(resolve(&base, "prog.da", &inside.to_string_lossy()).is_ok());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn data_dir_rejects_symlinks_that_lead_out() {
+        let dir = scratch("symlink");
+        std::os::unix::fs::symlink(dir.join("outside"), dir.join("data").join("link")).unwrap();
+        std::os::unix::fs::symlink(dir.join("outside").join("secret.csv"), dir.join("data").join("s.csv")).unwrap();
+        let base = PathBase::DataDir(dir.join("data"));
+        assert!(resolve(&base, "prog.da", "link/secret.csv").is_err());
+        assert!(resolve(&base, "prog.da", "s.csv").is_err());
+        assert!(resolve(&base, "prog.da", "link/new.csv").is_err());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn data_dir_may_itself_be_a_symlink() {
+        let dir = scratch("linked-root");
+        std::os::unix::fs::symlink(dir.join("data"), dir.join("root")).unwrap();
+        let base = PathBase::DataDir(dir.join("root"));
+        assert!(resolve(&base, "prog.da", "x.csv").is_ok());
+        assert!(resolve(&base, "prog.da", "../outside/secret.csv").is_err());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn data_dir_of_dot_is_the_working_directory() {
+        let base = PathBase::DataDir(PathBuf::from("."));
+        assert_eq!(resolve(&base, "prog.da", "x.csv").unwrap(), "x.csv");
+        assert_eq!(resolve(&base, "prog.da", "./sub/x.csv").unwrap(), "sub/x.csv");
+        assert!(resolve(&base, "prog.da", "../x.csv").is_err());
+        assert!(resolve(&base, "prog.da", "/etc/passwd").is_err());
+    }
+}
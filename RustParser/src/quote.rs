@@ -0,0 +1,262 @@
+// Writes text as literals of the generated languages, escaped so that any character survives
+// -> it's a "test" is 'it\'s a "test"' as a Prolog atom, "it's a \"test\"" as a Scheme, JavaScript, Rust
+// or Julia string and 'it''s a "test"' as an Octave one
+
+// Atoms that are operators in SWI-Prolog and so cannot stand on their own without quotes
+const PROLOG_OPERATORS: [&str; 15] = [
+    "is", "mod", "rem", "div", "rdiv", "xor", "as", "dynamic", "discontiguous", "initialization",
+    "meta_predicate", "module_transparent", "multifile", "public", "table",
+];
+
+// Words that cannot name a table or column in SQLite, DuckDB or PostgreSQL without quotes
+const SQL_KEYWORDS: [&str; 60] = [
+    "all", "and", "any", "as", "asc", "between", "both", "by", "case", "cast", "check", "collate", "column",
+    "constraint", "create", "cross", "default", "delete", "desc", "distinct", "do", "else", "end", "except",
+    "exists", "false", "for", "foreign", "from", "full", "group", "having", "in", "inner", "insert",
+    "intersect", "into", "is", "join", "left", "like", "limit", "natural", "not", "null", "offset", "on",
+    "or", "order", "outer", "primary", "references", "right", "select", "table", "then", "to", "true",
+    "union", "where",
+];
+
+// A Prolog atom for the text, quoted unless it is a plain lowercase name that is not an operator
+pub fn prolog_atom(text: &str) -> String {
+    let mut chars = text.chars();
+    let plain = chars.next().is_some_and(|first| first.is_ascii_lowercase())
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && !PROLOG_OPERATORS.contains(&text);
+    if plain {
+        return text.to_string();
+    }
+    prolog_quoted(text, '\'')
+}
+
+// A Prolog string, which SWI-Prolog reads as a string object -> "value of a = "
+pub fn prolog_string(text: &str) -> String {
+    prolog_quoted(text, '"')
+}
+
+fn prolog_quoted(text: &str, quote: char) -> String {
+    let mut out = String::new();
+    out.push(quote);
+    for c in text.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            '\r' => out.push_str("\\r"),
+            c if c == quote => {
+                out.push('\\');
+                out.push(c);
+            }
+            // Other control characters are written by code, which Prolog closes with a backslash
+            c if c.is_control() => out.push_str(&format!("\\x{:x}\\", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push(quote);
+    out
+}
+
+// An R7RS string literal
+pub fn scheme_string(text: &str) -> String {
+    let mut out = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '"' => out.push_str("\\\""),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            '\r' => out.push_str("\\r"),
+            // Scheme closes a character code with a semicolon
+            c if c.is_control() => out.push_str(&format!("\\x{:x};", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+// A JavaScript string literal, also valid as JSON
+pub fn javascript_string(text: &str) -> String {
+    let mut out = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '"' => out.push_str("\\\""),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            '\r' => out.push_str("\\r"),
+            // Line and paragraph separators end a line in older JavaScript, so they are written by code too
+            c if c.is_control() || c == '\u{2028}' || c == '\u{2029}' => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+// A SQL name, in double quotes unless it is a plain lowercase name that is not a keyword.
+// A dotted name is quoted part by part -> analytics.sales stays, Sales becomes "Sales"
+pub fn sql_identifier(name: &str) -> String {
+    let parts: Vec<String> = name
+        .split('.')
+        .map(|part| {
+            let mut chars = part.chars();
+            let plain = chars.next().is_some_and(|first| first.is_ascii_lowercase() || first == '_')
+                && chars.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+                && !SQL_KEYWORDS.contains(&part);
+            if plain {
+                part.to_string()
+            } else {
+                format!("\"{}\"", part.replace('"', "\"\""))
+            }
+        })
+        .collect();
+    parts.join(".")
+}
+
+// A Rust string literal
+pub fn rust_string(text: &str) -> String {
+    let mut out = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '"' => out.push_str("\\\""),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            '\r' => out.push_str("\\r"),
+            c if c.is_control() => out.push_str(&format!("\\u{{{:x}}}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+// A Julia string literal, $ is escaped as well so nothing is interpolated
+pub fn julia_string(text: &str) -> String {
+    let mut out = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '"' => out.push_str("\\\""),
+            '$' => out.push_str("\\$"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            '\r' => out.push_str("\\r"),
+            c if c.is_control() => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+// An Octave and MATLAB character array. Single quotes have no backslash escapes, so an apostrophe
+// is doubled and control characters are joined on with char -> ['a' char(9) 'b']
+pub fn octave_string(text: &str) -> String {
+    let mut parts = Vec::new();
+    let mut plain = String::new();
+    for c in text.chars() {
+        if c.is_control() {
+            if !plain.is_empty() {
+                parts.push(format!("'{}'", plain));
+                plain.clear();
+            }
+            parts.push(format!("char({})", c as u32));
+        } else if c == '\'' {
+            plain.push_str("''");
+        } else {
+            plain.push(c);
+        }
+    }
+    if !plain.is_empty() || parts.is_empty() {
+        parts.push(format!("'{}'", plain));
+    }
+    if parts.len() == 1 {
+        parts.remove(0)
+    } else {
+        format!("[{}]", parts.join(" "))
+    }
+}
+
+// A Graphviz string, a line break becomes the \n that starts a new line of a label
+pub fn dot_string(text: &str) -> String {
+    let mut out = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '"' => out.push_str("\\\""),
+            '\n' => out.push_str("\\n"),
+            c if c.is_control() => out.push(' '),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+// A DA string literal, the escapes of interpreter::unescape written back -> for da fmt
+pub fn da_string(text: &str) -> String {
+    let mut out = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '"' => out.push_str("\\\""),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            '\r' => out.push_str("\\r"),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prolog_quotes_only_when_needed() {
+        assert_eq!(prolog_atom("mean"), "mean");
+        assert_eq!(prolog_atom("is"), "'is'");
+        assert_eq!(prolog_atom("Data.csv"), "'Data.csv'");
+        assert_eq!(prolog_atom("it's"), r"'it\'s'");
+        assert_eq!(prolog_string("a\u{1}b\n"), r#""a\x1\b\n""#);
+    }
+
+    #[test]
+    fn string_literals_escape_quotes_and_control_characters() {
+        let text = "it's a \"test\"\t\u{1}";
+        assert_eq!(scheme_string(text), r#""it's a \"test\"\t\x1;""#);
+        assert_eq!(javascript_string(text), r#""it's a \"test\"\t\u0001""#);
+        assert_eq!(rust_string(text), r#""it's a \"test\"\t\u{1}""#);
+        assert_eq!(julia_string("$x"), r#""\$x""#);
+        assert_eq!(javascript_string("\u{2028}"), r#""\u2028""#);
+        assert_eq!(dot_string("a\nb\u{1}"), r#""a\nb ""#);
+    }
+
+    #[test]
+    fn octave_joins_control_characters_on() {
+        assert_eq!(octave_string("it's"), "'it''s'");
+        assert_eq!(octave_string(""), "''");
+        assert_eq!(octave_string("\t"), "char(9)");
+        assert_eq!(octave_string("a\tb"), "['a' char(9) 'b']");
+    }
+
+    #[test]
+    fn sql_quotes_keywords_and_capitals_part_by_part() {
+        assert_eq!(sql_identifier("analytics.sales"), "analytics.sales");
+        assert_eq!(sql_identifier("Sales"), "\"Sales\"");
+        assert_eq!(sql_identifier("order"), "\"order\"");
+        assert_eq!(sql_identifier("a.\"b"), "a.\"\"\"b\"");
+    }
+
+    #[test]
+    fn da_string_writes_back_the_escapes_unescape_reads() {
+        let text = "it\"s\\ a\tb\n";
+        assert_eq!(da_string(text), r#""it\"s\\ a\tb\n""#);
+        assert_eq!(crate::interpreter::unescape(r#"it\"s\\ a\tb\n"#), text);
+    }
+}
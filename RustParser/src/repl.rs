@@ -0,0 +1,111 @@
+use std::io::{self, BufRead, IsTerminal, Write};
+
+use da::ast::{DataDef, Statement};
+use da::interpreter::{Environment, Value};
+use da::{Diagnostic, TokenEdition};
+
+use crate::cli;
+
+const HELP: &str = "Enter one DA statement per line, from any section:
+   x : vector                      declare an ID
+   x = read(\"f.csv\", true, 0)      read a column
+   m = mean(x)                     compute a value
+   \"text\", m, write(\"f.csv\", m)   display or save values
+   missing = drop                  set the missing value policy for later reads
+A function call on its own, like mean(x), prints its value and keeps it as `it`.
+:vars lists the declared IDs and their values, :quit or end of input leaves.";
+
+// Reads statements from stdin one line at a time and runs each one as soon as it is complete
+pub fn repl() -> i32 {
+    let mut env = Environment::default();
+    let stdin = io::stdin();
+    // Statements piped in from a file get no prompt in between their output
+    let interactive = stdin.is_terminal();
+    let mut lines = stdin.lock().lines();
+
+    if interactive {
+        println!("da {}, :help for help", env!("CARGO_PKG_VERSION"));
+    }
+    loop {
+        if interactive {
+            print!("da> ");
+            io::stdout().flush().unwrap();
+        }
+        let line = match lines.next() {
+            Some(Ok(line)) => line,
+            _ => break,
+        };
+
+        match line.trim() {
+            "" => {}
+            ":quit" | ":q" => break,
+            ":help" => println!("{}", HELP),
+            ":vars" => vars(&env),
+            line => {
+                if let Err(diagnostic) = evaluate(&mut env, line) {
+                    println!("error: {}", diagnostic);
+                }
+            }
+        }
+    }
+    if interactive {
+        println!();
+    }
+    cli::EXIT_OK
+}
+
+fn evaluate(env: &mut Environment, line: &str) -> Result<(), Diagnostic> {
+    // mean(x) on its own is read as it = mean(x)
+    let first = da::lex(line).ok().and_then(|tokens| tokens.first().map(|token| token.kind()));
+    let bare_call = matches!(
+        first,
+        Some(
+            TokenEdition::MEAN
+                | TokenEdition::STDDEV
+                | TokenEdition::CORRELATION
+                | TokenEdition::REGRESSIONA
+                | TokenEdition::REGRESSIONB
+        )
+    );
+    let source = if bare_call { format!("it = {}", line) } else { line.to_string() };
+
+    let statement = match da::parse_statement(&source)? {
+        Some(statement) => statement,
+        None => return Ok(()),
+    };
+    match statement {
+        Statement::Missing(policy) => env.missing = Some(policy),
+        Statement::Data(def) => env.declare(def),
+        Statement::Input(input) => {
+            env.input(&input)?;
+            show(env, &input.id);
+        }
+        Statement::Process(process) => {
+            if bare_call {
+                env.declare(DataDef { id: process.id.clone(), kind: TokenEdition::NUMBER });
+            }
+            env.process(&process)?;
+            show(env, &process.id);
+        }
+        Statement::Output(output) => env.output(&output)?,
+    }
+    Ok(())
+}
+
+// Echoes a value that was just assigned -> m = 2.5
+fn show(env: &Environment, id: &str) {
+    if let Some(value) = env.get(id) {
+        println!("{} = {}", id, value);
+    }
+}
+
+fn vars(env: &Environment) {
+    for def in env.declarations() {
+        let kind = if def.kind == TokenEdition::NUMBER { "number" } else { "vector" };
+        match env.get(&def.id) {
+            Some(value @ Value::Vector(v)) => println!("{} : {} = {} ({} values)", def.id, kind, value, v.len()),
+            Some(value) => println!("{} : {} = {}", def.id, kind, value),
+            None => println!("{} : {}", def.id, kind),
+        }
+    }
+}
@@ -0,0 +1,27 @@
+// Support code bundled into standalone builds, so the generated program runs with nothing else installed
+// Some targets carry theirs in every build, see embedded
+
+const SCHEME: &str = include_str!("runtime/scheme.scm");
+const PROLOG: &str = include_str!("runtime/prolog.pl");
+const JAVASCRIPT: &str = include_str!("runtime/javascript.mjs");
+const JULIA: &str = include_str!("runtime/julia.jl");
+const RUST: &str = include_str!("runtime/rust.rs");
+
+// The code a standalone program for the target starts with, None when the target has no runtime
+pub fn runtime(target: &str) -> Option<&'static str> {
+    match target {
+        "scheme" => Some(SCHEME),
+        "prolog" => Some(PROLOG),
+        _ => None,
+    }
+}
+
+// The support code every program for the target contains, so its builds run on their own anyway
+pub fn embedded(target: &str) -> Option<&'static str> {
+    match target {
+        "javascript" => Some(JAVASCRIPT),
+        "julia" => Some(JULIA),
+        "rust" => Some(RUST),
+        _ => None,
+    }
+}
@@ -0,0 +1,303 @@
+// Runtime of the DA JavaScript target, every generated module contains it
+// File access goes through io, which uses node:fs under Node. A browser bundle hands its own
+// readFile and writeFile to setIo before it calls main:
+//   setIo({ readFile: (name) => files[name], writeFile: (name, text) => download(name, text) })
+
+const nodeFs = typeof process !== "undefined" && process.versions?.node ? await import("node:fs") : null;
+
+let io = {
+  // The bytes of the file, or its text when the host already has it as a string
+  readFile: (name) => {
+    if (!nodeFs) throw new Error(`Could not read ${name}: no file system, give one with setIo`);
+    try {
+      return nodeFs.readFileSync(name);
+    } catch (e) {
+      throw new Error(`Could not read ${name}: ${e.message}`);
+    }
+  },
+  writeFile: (name, text) => {
+    if (!nodeFs) throw new Error(`Could not write ${name}: no file system, give one with setIo`);
+    try {
+      nodeFs.writeFileSync(name, text);
+    } catch (e) {
+      throw new Error(`Could not write ${name}: ${e.message}`);
+    }
+  },
+  log: (text) => console.log(text),
+};
+
+export function setIo(custom) {
+  io = { ...io, ...custom };
+}
+
+// ---- Reading ----
+
+// Turns the raw bytes of a file into text, utf-8 is assumed unless told otherwise
+function decode(data, encoding) {
+  if (typeof data === "string") return data;
+  const name = (encoding ?? "utf-8").toLowerCase();
+  if (["utf-8", "utf8"].includes(name)) return new TextDecoder("utf-8").decode(data);
+  if (["latin1", "latin-1", "iso-8859-1"].includes(name)) return Array.from(data, (b) => String.fromCharCode(b)).join("");
+  throw new Error(`Unsupported encoding ${encoding}, use utf-8 or latin1`);
+}
+
+function singleChar(value, fallback, name) {
+  if (value === undefined) return fallback;
+  if ([...value].length !== 1) throw new Error(`The ${name} must be a single character, found ${JSON.stringify(value)}`);
+  return value;
+}
+
+// Splits text into records and fields, a quoted field may contain the delimiter, newlines
+// and doubled quote characters
+function splitRecords(text, delimiter, quote) {
+  const records = [];
+  let record = [];
+  let field = "";
+  let inQuote = false;
+  for (let i = 0; i < text.length; i++) {
+    const c = text[i];
+    if (inQuote) {
+      if (c === quote && text[i + 1] === quote) {
+        field += quote;
+        i++;
+      } else if (c === quote) {
+        inQuote = false;
+      } else {
+        field += c;
+      }
+    } else if (c === quote) {
+      inQuote = true;
+    } else if (c === delimiter) {
+      record.push(field);
+      field = "";
+    } else if (c === "\n" || c === "\r") {
+      if (c === "\r" && text[i + 1] === "\n") i++;
+      record.push(field);
+      field = "";
+      // Blank lines are skipped
+      if (!(record.length === 1 && record[0] === "")) records.push(record);
+      record = [];
+    } else {
+      field += c;
+    }
+  }
+  if (field !== "" || record.length > 0) {
+    record.push(field);
+    records.push(record);
+  }
+  return records;
+}
+
+// Blank cells and the usual spellings of "not available" are missing values
+function isMissing(field) {
+  return ["", "na", "n/a", "nan", "null"].includes(field.toLowerCase());
+}
+
+// A dropped value is kept as NaN so vectors read from the same file stay aligned
+function missingValue(options, place) {
+  if (options.missing === "drop") return NaN;
+  if (options.missing === "zero") return 0;
+  throw new Error(`${place} is missing, use missing = drop or missing = zero to allow missing values`);
+}
+
+// rows = [10, 500] keeps the data rows 10 up to but not including 500, [10, null] has no end
+function inRows(options, row) {
+  if (!options.rows) return true;
+  const [start, end] = options.rows;
+  return row >= start && (end === null || row < end);
+}
+
+// One column of a delimited file, the first row is skipped when it is a header
+export function readCsv(file, header, column, options = {}) {
+  const text = decode(io.readFile(file), options.encoding);
+  const delimiter = singleChar(options.delimiter, ",", "delimiter");
+  const quote = singleChar(options.quote, '"', "quote");
+  const values = [];
+  splitRecords(text, delimiter, quote).forEach((record, row) => {
+    if (header && row === 0) return;
+    if (!inRows(options, header ? row - 1 : row)) return;
+    // A short row counts as a missing cell
+    const field = (record[column] ?? "").trim();
+    if (isMissing(field)) {
+      if (!options.skipMissing) values.push(missingValue(options, `${file} row ${row + 1} column ${column}`));
+      return;
+    }
+    const value = Number(field);
+    if (Number.isNaN(value)) throw new Error(`${file} row ${row + 1} column ${column}: ${JSON.stringify(field)} is not a number`);
+    values.push(value);
+  });
+  return values;
+}
+
+export function readTsv(file, header, column, options = {}) {
+  return readCsv(file, header, column, { ...options, delimiter: "\t" });
+}
+
+// Evaluates the supported subset of JSON path -> $ .name ['name'] [n] [*] .*
+function select(document, path) {
+  if (!path.startsWith("$")) throw new Error(`A JSON path has to start with $, found ${path}`);
+  let nodes = [document];
+  let i = 1;
+  while (i < path.length) {
+    let step;
+    if (path[i] === ".") {
+      const start = ++i;
+      while (i < path.length && path[i] !== "." && path[i] !== "[") i++;
+      step = path.slice(start, i);
+    } else if (path[i] === "[") {
+      const end = path.indexOf("]", i);
+      if (end < 0) throw new Error(`Unclosed [ in JSON path ${path}`);
+      step = path.slice(i + 1, end).replace(/^['"]+|['"]+$/g, "");
+      i = end + 1;
+    } else {
+      throw new Error(`Unexpected ${path[i]} in JSON path ${path}`);
+    }
+    const next = [];
+    for (const node of nodes) {
+      if (node === null || typeof node !== "object") continue;
+      if (step === "*") {
+        next.push(...(Array.isArray(node) ? node : Object.values(node)));
+      } else if (Array.isArray(node)) {
+        if (/^\d+$/.test(step) && Number(step) < node.length) next.push(node[Number(step)]);
+      } else if (Object.hasOwn(node, step)) {
+        next.push(node[step]);
+      }
+    }
+    nodes = next;
+  }
+  return nodes;
+}
+
+function jsonNumber(file, path, node) {
+  if (typeof node !== "number") throw new Error(`${file} ${path}: ${JSON.stringify(node)} is not a number`);
+  return node;
+}
+
+// Every number selected by a JSON path such as $.items[*].price
+export function readJson(file, path, options = {}) {
+  const text = decode(io.readFile(file), options.encoding);
+  let document;
+  try {
+    document = JSON.parse(text);
+  } catch (e) {
+    throw new Error(`${file} is not valid JSON: ${e.message}`);
+  }
+  const values = [];
+  select(document, path).forEach((node, index) => {
+    if (!inRows(options, index)) return;
+    if (node === null) {
+      if (!options.skipMissing) values.push(missingValue(options, `${file} ${path} value ${index}`));
+      return;
+    }
+    // A path that ends on an array of numbers selects all of them
+    if (Array.isArray(node)) node.forEach((item) => values.push(jsonNumber(file, path, item)));
+    else values.push(jsonNumber(file, path, node));
+  });
+  return values;
+}
+
+// ---- Statistics ----
+
+// Missing values dropped while reading are NaN, every statistic leaves them out
+function present(x) {
+  return x.filter((v) => !Number.isNaN(v));
+}
+
+// Pairwise functions keep only the pairs where both values are present
+function completePairs(x, y) {
+  if (x.length !== y.length) {
+    throw new Error(`Pairwise functions need vectors of the same length, found ${x.length} and ${y.length}`);
+  }
+  const keep = x.map((_, i) => !Number.isNaN(x[i]) && !Number.isNaN(y[i]));
+  return [x.filter((_, i) => keep[i]), y.filter((_, i) => keep[i])];
+}
+
+export function mean(x) {
+  const values = present(x);
+  if (values.length === 0) throw new Error("mean of an empty vector");
+  return values.reduce((sum, v) => sum + v, 0) / values.length;
+}
+
+// Sample standard deviation
+export function stddev(x) {
+  const values = present(x);
+  if (values.length < 2) throw new Error("stddev needs at least two values");
+  const m = mean(values);
+  return Math.sqrt(values.reduce((sum, v) => sum + (v - m) * (v - m), 0) / (values.length - 1));
+}
+
+function sumsOfSquares(x, y) {
+  const mx = mean(x);
+  const my = mean(y);
+  let sxx = 0;
+  let syy = 0;
+  let sxy = 0;
+  x.forEach((a, i) => {
+    sxx += (a - mx) * (a - mx);
+    syy += (y[i] - my) * (y[i] - my);
+    sxy += (a - mx) * (y[i] - my);
+  });
+  return [sxx, syy, sxy];
+}
+
+// Pearson correlation coefficient
+export function correlation(x, y) {
+  const [sxx, syy, sxy] = sumsOfSquares(...completePairs(x, y));
+  return sxy / Math.sqrt(sxx * syy);
+}
+
+// Intercept a of the least squares line y = a + bx
+export function regressiona(x, y) {
+  const [px, py] = completePairs(x, y);
+  return mean(py) - regressionb(px, py) * mean(px);
+}
+
+// Slope b of the least squares line y = a + bx
+export function regressionb(x, y) {
+  const [sxx, , sxy] = sumsOfSquares(...completePairs(x, y));
+  return sxy / sxx;
+}
+
+// ---- Output ----
+
+// A vector is shown the way Scheme displays a list -> (1 2 3)
+export function show(value) {
+  return Array.isArray(value) ? `(${value.join(" ")})` : String(value);
+}
+
+// Saves named values, as a JSON object when the file ends in .json and as CSV otherwise
+// In the CSV a number only fills the first row and a vector as many rows as it has values
+export function writeResults(file, values) {
+  const names = Object.keys(values);
+  let text;
+  if (file.toLowerCase().endsWith(".json")) {
+    text = JSON.stringify(values, null, 2) + "\n";
+  } else {
+    const rows = Math.max(0, ...names.map((name) => (Array.isArray(values[name]) ? values[name].length : 1)));
+    const lines = [names.join(",")];
+    for (let row = 0; row < rows; row++) {
+      const cells = names.map((name) => {
+        const value = values[name];
+        if (!Array.isArray(value)) return row === 0 ? String(value) : "";
+        return row < value.length && !Number.isNaN(value[row]) ? String(value[row]) : "";
+      });
+      lines.push(cells.join(","));
+    }
+    text = lines.join("\n") + "\n";
+  }
+  io.writeFile(file, text);
+}
+
+// Runs main when Node was started on this file, and only reports the message of an error
+async function runAsScript(main) {
+  if (!nodeFs || !process.argv[1]) return;
+  const { pathToFileURL } = await import("node:url");
+  const { realpathSync } = nodeFs;
+  if (import.meta.url !== pathToFileURL(realpathSync(process.argv[1])).href) return;
+  try {
+    main();
+  } catch (e) {
+    console.error(`error: ${e.message}`);
+    process.exitCode = 1;
+  }
+}
@@ -0,0 +1,396 @@
+/* DA runtime for SWI-Prolog, put in front of the program by da build --target prolog --standalone
+   It reads and computes the same way da run does, so the file runs on its own with swipl file.pl.
+   A dropped missing value is kept as the atom missing, statistics leave it out. */
+
+:- module(da_program, [main/0]).
+
+:- use_module(library(apply)).
+:- use_module(library(csv)).
+:- use_module(library(http/json)).
+:- use_module(library(lists)).
+:- use_module(library(option)).
+:- use_module(library(pairs)).
+
+% Reports an error in the DA program and stops with exit code 1, as da run does
+da_fail(Format, Args) :-
+    format(user_error, "error: ", []),
+    format(user_error, Format, Args),
+    nl(user_error),
+    halt(1).
+
+% ---------------------------------------------------------------- options
+
+da_char_option(Options, Name, Default, Code) :-
+    Option =.. [Name, Value],
+    (   option(Option, Options)
+    ->  (   string_length(Value, 1)
+        ->  string_code(1, Value, Code)
+        ;   da_fail("The ~w must be a single character, found ~q", [Name, Value])
+        )
+    ;   Code = Default
+    ).
+
+da_encoding(Options, Encoding) :-
+    (   option(encoding(Name), Options)
+    ->  string_lower(Name, Lower),
+        (   memberchk(Lower, ["utf-8", "utf8"])
+        ->  Encoding = utf8
+        ;   memberchk(Lower, ["latin1", "latin-1", "iso-8859-1"])
+        ->  Encoding = iso_latin_1
+        ;   da_fail("Unsupported encoding ~w, use utf-8 or latin1", [Name])
+        )
+    ;   Encoding = utf8
+    ).
+
+% rows(Start, End) keeps the data rows Start up to but not including End, End is inf for an open range
+da_in_rows(Options, Index) :-
+    (   memberchk(rows(Start, End), Options)
+    ->  Index >= Start,
+        (   End == inf
+        ->  true
+        ;   Index < End
+        )
+    ;   true
+    ).
+
+% ---------------------------------------------------------------- missing values
+
+% Blank cells and the usual spellings of "not available" are missing values
+da_missing(Field) :-
+    downcase_atom(Field, Lower),
+    memberchk(Lower, ['', na, 'n/a', nan, null]).
+
+da_missing_value(Options, Place, Value) :-
+    (   option(missing(drop), Options)
+    ->  Value = missing
+    ;   option(missing(zero), Options)
+    ->  Value = 0
+    ;   da_fail("~w is missing, use missing = drop or missing = zero to allow missing values", [Place])
+    ).
+
+% ---------------------------------------------------------------- readers
+
+load_data_column(File, Header, Column, Values) :-
+    load_data_column(File, Header, Column, [], Values).
+
+load_data_column(File, Header, Column, Options, Values) :-
+    da_read_column(File, Header, Column, Options, 0',, Values).
+
+load_tsv_column(File, Header, Column, Values) :-
+    load_tsv_column(File, Header, Column, [], Values).
+
+load_tsv_column(File, Header, Column, Options, Values) :-
+    da_read_column(File, Header, Column, Options, 0'\t, Values).
+
+da_read_column(File, Header, Column, Options, DefaultDelimiter, Values) :-
+    da_char_option(Options, delimiter, DefaultDelimiter, Separator),
+    da_char_option(Options, quote, 0'", Quote),
+    da_encoding(Options, Encoding),
+    % library(csv) only knows double quotes, any other quote character is taken off each field afterwards
+    (   Quote == 0'"
+    ->  QuoteOptions = []
+    ;   QuoteOptions = [ignore_quotes(true)]
+    ),
+    CsvOptions = [separator(Separator), convert(false), strip(true), match_arity(false), encoding(Encoding)|QuoteOptions],
+    catch(csv_read_file(File, Rows0, CsvOptions), Error,
+          da_fail("Could not read ~w: ~p", [File, Error])),
+    exclude(da_blank_row, Rows0, Rows1),
+    (   Header == true, Rows1 = [_|Rows]
+    ->  FirstLine = 2
+    ;   Rows = Rows1,
+        FirstLine = 1
+    ),
+    da_cells(Rows, 0, FirstLine, File, Column, Quote, Options, Values).
+
+da_blank_row(row('')).
+
+da_cells([], _, _, _, _, _, _, []).
+da_cells([Row|Rows], Index, Line, File, Column, Quote, Options, Values) :-
+    (   \+ da_in_rows(Options, Index)
+    ->  Values = Rest
+    ;   da_field(Row, Column, Quote, Field),
+        format(atom(Place), "~w row ~w column ~w", [File, Line, Column]),
+        (   da_missing(Field)
+        ->  (   option(skip_missing(true), Options)
+            ->  Values = Rest
+            ;   da_missing_value(Options, Place, Value),
+                Values = [Value|Rest]
+            )
+        ;   atom_number(Field, Value)
+        ->  Values = [Value|Rest]
+        ;   da_fail("~w: ~q is not a number", [Place, Field])
+        )
+    ),
+    Next is Index + 1,
+    NextLine is Line + 1,
+    da_cells(Rows, Next, NextLine, File, Column, Quote, Options, Rest).
+
+% A short row counts as a missing cell
+da_field(Row, Column, Quote, Field) :-
+    N is Column + 1,
+    functor(Row, _, Arity),
+    (   N =< Arity
+    ->  arg(N, Row, Field0),
+        da_unquote(Quote, Field0, Field)
+    ;   Field = ''
+    ).
+
+da_unquote(Quote, Field0, Field) :-
+    atom_codes(Field0, Codes),
+    (   Codes = [Quote|Rest],
+        append(Inner, [Quote], Rest)
+    ->  atom_codes(Field, Inner)
+    ;   Field = Field0
+    ).
+
+% ---------------------------------------------------------------- JSON
+
+load_json_values(File, Path, Values) :-
+    load_json_values(File, Path, [], Values).
+
+load_json_values(File, Path, Options, Values) :-
+    da_encoding(Options, Encoding),
+    catch(setup_call_cleanup(open(File, read, Stream, [encoding(Encoding)]),
+                             json_read_dict(Stream, Document),
+                             close(Stream)),
+          Error,
+          da_fail("Could not read ~w: ~p", [File, Error])),
+    da_json_steps(Path, Steps),
+    da_select(Steps, [Document], Nodes),
+    da_json_values(Nodes, 0, File, Path, Options, Values).
+
+% "$.items[*].price" -> [items, '*', price], the supported subset is $ .name ['name'] [n] [*] .*
+da_json_steps(Path, Steps) :-
+    string_chars(Path, ['$'|Chars]),
+    !,
+    da_steps(Chars, Path, Steps).
+da_json_steps(Path, _) :-
+    da_fail("A JSON path has to start with $, found ~w", [Path]).
+
+da_steps([], _, []).
+da_steps(['.'|Chars], Path, [Step|Steps]) :-
+    !,
+    da_take_name(Chars, Name, Rest),
+    atom_chars(Step, Name),
+    da_steps(Rest, Path, Steps).
+da_steps(['['|Chars], Path, [Step|Steps]) :-
+    !,
+    (   append(Inside, [']'|Rest], Chars)
+    ->  exclude(da_quote_char, Inside, Name),
+        atom_chars(Step, Name),
+        da_steps(Rest, Path, Steps)
+    ;   da_fail("Unclosed [ in JSON path ~w", [Path])
+    ).
+da_steps([Char|_], Path, _) :-
+    da_fail("Unexpected ~w in JSON path ~w", [Char, Path]).
+
+da_take_name([Char|Chars], [Char|Name], Rest) :-
+    Char \== '.',
+    Char \== '[',
+    !,
+    da_take_name(Chars, Name, Rest).
+da_take_name(Rest, [], Rest).
+
+da_quote_char('\'').
+da_quote_char('"').
+
+da_select([], Nodes, Nodes).
+da_select([Step|Steps], Nodes0, Nodes) :-
+    maplist(da_json_step(Step), Nodes0, Children),
+    append(Children, Nodes1),
+    da_select(Steps, Nodes1, Nodes).
+
+da_json_step('*', Node, Children) :-
+    !,
+    (   is_list(Node)
+    ->  Children = Node
+    ;   is_dict(Node)
+    ->  dict_pairs(Node, _, Pairs),
+        pairs_values(Pairs, Children)
+    ;   Children = []
+    ).
+da_json_step(Step, Node, Children) :-
+    (   is_list(Node),
+        atom_number(Step, Index),
+        integer(Index)
+    ->  (   nth0(Index, Node, Child)
+        ->  Children = [Child]
+        ;   Children = []
+        )
+    ;   is_dict(Node),
+        get_dict(Step, Node, Child)
+    ->  Children = [Child]
+    ;   Children = []
+    ).
+
+da_json_values([], _, _, _, _, []).
+da_json_values([Node|Nodes], Index, File, Path, Options, Values) :-
+    format(atom(Place), "~w ~w value ~w", [File, Path, Index]),
+    (   \+ da_in_rows(Options, Index)
+    ->  Values = Rest
+    ;   Node == null
+    ->  (   option(skip_missing(true), Options)
+        ->  Values = Rest
+        ;   da_missing_value(Options, Place, Value),
+            Values = [Value|Rest]
+        )
+    ;   is_list(Node)
+    ->  % A path that ends on an array of numbers selects all of them
+        maplist(da_json_number(Place), Node, Numbers),
+        append(Numbers, Rest, Values)
+    ;   da_json_number(Place, Node, Value),
+        Values = [Value|Rest]
+    ),
+    Next is Index + 1,
+    da_json_values(Nodes, Next, File, Path, Options, Rest).
+
+da_json_number(_, Node, Node) :-
+    number(Node),
+    !.
+da_json_number(Place, Node, _) :-
+    da_fail("~w: ~q is not a number", [Place, Node]).
+
+% ---------------------------------------------------------------- statistics
+
+% Statistics leave out dropped values
+da_present(Xs, Present) :-
+    include(number, Xs, Present).
+
+% Pairwise functions keep only the pairs where both values are present
+da_complete_pairs(Xs, Ys, CompleteXs, CompleteYs) :-
+    length(Xs, N),
+    length(Ys, M),
+    (   N =:= M
+    ->  true
+    ;   da_fail("Pairwise functions need vectors of the same length, found ~w and ~w", [N, M])
+    ),
+    pairs_keys_values(Pairs, Xs, Ys),
+    include(da_complete_pair, Pairs, Complete),
+    pairs_keys_values(Complete, CompleteXs, CompleteYs).
+
+da_complete_pair(X-Y) :-
+    number(X),
+    number(Y).
+
+mean(Xs, Mean) :-
+    da_present(Xs, Present),
+    length(Present, N),
+    (   N > 0
+    ->  true
+    ;   da_fail("mean of an empty vector", [])
+    ),
+    sum_list(Present, Sum),
+    Mean is Sum / N.
+
+% Sample standard deviation
+stddev(Xs, StdDev) :-
+    da_present(Xs, Present),
+    length(Present, N),
+    (   N > 1
+    ->  true
+    ;   da_fail("stddev needs at least two values", [])
+    ),
+    mean(Present, Mean),
+    foldl(da_add_square(Mean), Present, 0, SumSq),
+    StdDev is sqrt(SumSq / (N - 1)).
+
+da_add_square(Mean, X, Sum0, Sum) :-
+    Sum is Sum0 + (X - Mean) * (X - Mean).
+
+% Sums of squares and products around the means
+da_sums(Xs, Ys, Sxx, Syy, Sxy) :-
+    mean(Xs, Mx),
+    mean(Ys, My),
+    foldl(da_add_products(Mx, My), Xs, Ys, 0-0-0, Sxx-Syy-Sxy).
+
+da_add_products(Mx, My, X, Y, Sxx0-Syy0-Sxy0, Sxx-Syy-Sxy) :-
+    Sxx is Sxx0 + (X - Mx) * (X - Mx),
+    Syy is Syy0 + (Y - My) * (Y - My),
+    Sxy is Sxy0 + (X - Mx) * (Y - My).
+
+% Pearson correlation coefficient
+correlation(Xs, Ys, R) :-
+    da_complete_pairs(Xs, Ys, Px, Py),
+    da_sums(Px, Py, Sxx, Syy, Sxy),
+    R is Sxy / sqrt(Sxx * Syy).
+
+% Slope b of the least squares line y = a + bx
+regressionb(Xs, Ys, B) :-
+    da_complete_pairs(Xs, Ys, Px, Py),
+    da_sums(Px, Py, Sxx, _, Sxy),
+    B is Sxy / Sxx.
+
+% Intercept a of the least squares line y = a + bx
+regressiona(Xs, Ys, A) :-
+    da_complete_pairs(Xs, Ys, Px, Py),
+    regressionb(Px, Py, B),
+    mean(Px, Mx),
+    mean(Py, My),
+    A is My - B * Mx.
+
+% ---------------------------------------------------------------- write
+
+% Saves named values to a file, as a JSON object when the file ends in .json and as CSV otherwise
+% -> write_results('results.csv', [a-Va, x-Vx])
+write_results(File, Pairs) :-
+    file_name_extension(_, Extension, File),
+    downcase_atom(Extension, Lower),
+    (   Lower == json
+    ->  da_write_json(File, Pairs)
+    ;   da_write_csv(File, Pairs)
+    ).
+
+da_write_json(File, Pairs) :-
+    maplist(da_json_pair, Pairs, JsonPairs),
+    dict_pairs(Dict, _, JsonPairs),
+    setup_call_cleanup(open(File, write, Stream),
+                       ( json_write_dict(Stream, Dict), nl(Stream) ),
+                       close(Stream)).
+
+da_json_pair(Name-Value, Name-Json) :-
+    (   is_list(Value)
+    ->  maplist(da_json_value, Value, Json)
+    ;   da_json_value(Value, Json)
+    ).
+
+da_json_value(missing, null) :-
+    !.
+da_json_value(Value, Value).
+
+% One column per name, a number only fills the first row and a vector fills as many rows as it has values
+da_write_csv(File, Pairs) :-
+    pairs_keys_values(Pairs, Names, Values),
+    maplist(da_column_length, Values, Lengths),
+    max_list([0|Lengths], Count),
+    HeaderRow =.. [row|Names],
+    (   Count > 0
+    ->  Last is Count - 1,
+        findall(Row, (between(0, Last, Index), da_csv_row(Values, Index, Row)), Rows)
+    ;   Rows = []
+    ),
+    csv_write_file(File, [HeaderRow|Rows]).
+
+da_column_length(Value, Length) :-
+    (   is_list(Value)
+    ->  length(Value, Length)
+    ;   Length = 1
+    ).
+
+da_csv_row(Values, Index, Row) :-
+    maplist(da_csv_cell(Index), Values, Cells),
+    Row =.. [row|Cells].
+
+da_csv_cell(Index, Value, Cell) :-
+    (   is_list(Value)
+    ->  (   nth0(Index, Value, Item),
+            number(Item)
+        ->  Cell = Item
+        ;   Cell = ''
+        )
+    ;   Index =:= 0
+    ->  Cell = Value
+    ;   Cell = ''
+    ).
+
+% Runs main once the file is loaded and halts, with exit code 1 when it fails or raises an error
+:- initialization(main, main).
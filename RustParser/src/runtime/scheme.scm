@@ -0,0 +1,375 @@
+;; DA runtime for R7RS Scheme, put in front of the program by da build --target scheme --standalone
+;; It reads and computes the same way da run does. The encoding option is not available in portable
+;; R7RS, files are read in the encoding the Scheme system uses by default.
+(import (scheme base) (scheme char) (scheme file) (scheme inexact) (scheme write))
+
+;; ---------------------------------------------------------------- options
+
+;; The value of an option in the association list a read is given -> '((delimiter . ";") (rows 10 500))
+(define (da-option options name default)
+  (let ((entry (assq name options)))
+    (if entry (cdr entry) default)))
+
+(define (da-char-option options name default)
+  (let ((value (da-option options name #f)))
+    (cond ((not value) default)
+          ((= (string-length value) 1) (string-ref value 0))
+          (else (error "da: the option must be a single character" name value)))))
+
+;; rows is (start end) with end #f for an open range, counted from 0 after any header
+(define (da-in-rows? rows row)
+  (or (not rows)
+      (and (>= row (car rows))
+           (or (not (cadr rows)) (< row (cadr rows))))))
+
+;; ---------------------------------------------------------------- text
+
+(define (da-read-file path)
+  (call-with-input-file path
+    (lambda (port)
+      (let loop ((chunks '()))
+        (let ((chunk (read-string 4096 port)))
+          (if (eof-object? chunk)
+              (apply string-append (reverse chunks))
+              (loop (cons chunk chunks))))))))
+
+(define (da-trim text)
+  (let loop ((start 0) (end (string-length text)))
+    (cond ((and (< start end) (char-whitespace? (string-ref text start))) (loop (+ start 1) end))
+          ((and (< start end) (char-whitespace? (string-ref text (- end 1)))) (loop start (- end 1)))
+          (else (substring text start end)))))
+
+(define (da-suffix? text suffix)
+  (let ((n (string-length text)) (k (string-length suffix)))
+    (and (>= n k) (string=? (substring text (- n k) n) suffix))))
+
+;; Splits text into records and fields, a quoted field may contain the delimiter, newlines
+;; and doubled quote characters. Blank lines are skipped.
+(define (da-split-records text delimiter quote-char)
+  (define n (string-length text))
+  (define (finish field fields records)
+    (let ((record (reverse (cons (list->string (reverse field)) fields))))
+      (if (and (null? (cdr record)) (string=? (car record) ""))
+          records
+          (cons record records))))
+  (let loop ((i 0) (field '()) (fields '()) (records '()) (in-quote #f))
+    (if (= i n)
+        (reverse (if (and (null? field) (null? fields)) records (finish field fields records)))
+        (let ((c (string-ref text i)))
+          (cond (in-quote
+                 (cond ((and (char=? c quote-char) (< (+ i 1) n) (char=? (string-ref text (+ i 1)) quote-char))
+                        (loop (+ i 2) (cons c field) fields records #t))
+                       ((char=? c quote-char) (loop (+ i 1) field fields records #f))
+                       (else (loop (+ i 1) (cons c field) fields records #t))))
+                ((char=? c quote-char) (loop (+ i 1) field fields records #t))
+                ((char=? c delimiter) (loop (+ i 1) '() (cons (list->string (reverse field)) fields) records #f))
+                ((char=? c #\return)
+                 (let ((next (if (and (< (+ i 1) n) (char=? (string-ref text (+ i 1)) #\newline)) (+ i 2) (+ i 1))))
+                   (loop next '() '() (finish field fields records) #f)))
+                ((char=? c #\newline) (loop (+ i 1) '() '() (finish field fields records) #f))
+                (else (loop (+ i 1) (cons c field) fields records #f)))))))
+
+;; ---------------------------------------------------------------- missing values
+
+;; Blank cells and the usual spellings of "not available" are missing values
+(define (da-missing? field)
+  (member (string-downcase field) '("" "na" "n/a" "nan" "null")))
+
+;; A dropped cell is kept as +nan.0 so vectors read from the same file stay aligned
+(define (da-missing-value policy place)
+  (case policy
+    ((drop) +nan.0)
+    ((zero) 0)
+    (else (error "da: missing value, use missing = drop or missing = zero to allow missing values" place))))
+
+;; ---------------------------------------------------------------- readers
+
+(define (read-csv path header column . options)
+  (da-read-column path header column (if (null? options) '() (car options)) #\,))
+
+(define (read-tsv path header column . options)
+  (da-read-column path header column (if (null? options) '() (car options)) #\tab))
+
+(define (da-read-column path header column options default-delimiter)
+  (let* ((delimiter (da-char-option options 'delimiter default-delimiter))
+         (quote-char (da-char-option options 'quote #\"))
+         (rows (da-option options 'rows #f))
+         (skip-missing (da-option options 'skip-missing #f))
+         (policy (da-option options 'missing #f))
+         (records (da-split-records (da-read-file path) delimiter quote-char)))
+    (let loop ((records (if (and header (pair? records)) (cdr records) records))
+               (row 0)
+               (result '()))
+      (if (null? records)
+          (reverse result)
+          (let* ((record (car records))
+                 (place (list path 'row (+ row (if header 2 1)) 'column column))
+                 (field (if (< column (length record)) (da-trim (list-ref record column)) "")))
+            (cond ((not (da-in-rows? rows row)) (loop (cdr records) (+ row 1) result))
+                  ((da-missing? field)
+                   (if skip-missing
+                       (loop (cdr records) (+ row 1) result)
+                       (loop (cdr records) (+ row 1) (cons (da-missing-value policy place) result))))
+                  ((string->number field)
+                   => (lambda (n) (loop (cdr records) (+ row 1) (cons n result))))
+                  (else (error "da: not a number" place field))))))))
+
+;; ---------------------------------------------------------------- JSON
+
+;; Objects become (object (key . value) ...), arrays (array value ...) and null the symbol null
+(define (da-parse-json text)
+  (define n (string-length text))
+  (define i 0)
+  (define (skip-space)
+    (when (and (< i n) (char-whitespace? (string-ref text i)))
+      (set! i (+ i 1))
+      (skip-space)))
+  (define (next-char)
+    (skip-space)
+    (if (< i n) (string-ref text i) (error "da: unexpected end of JSON")))
+  (define (expect c)
+    (if (char=? (next-char) c)
+        (set! i (+ i 1))
+        (error "da: invalid JSON, expected" c 'at i)))
+  (define (word? word)
+    (let ((end (+ i (string-length word))))
+      (and (<= end n) (string=? (substring text i end) word))))
+  (define (parse-value)
+    (let ((c (next-char)))
+      (cond ((char=? c #\{) (set! i (+ i 1)) (parse-object))
+            ((char=? c #\[) (set! i (+ i 1)) (parse-array))
+            ((char=? c #\") (set! i (+ i 1)) (parse-string))
+            ((word? "true") (set! i (+ i 4)) #t)
+            ((word? "false") (set! i (+ i 5)) #f)
+            ((word? "null") (set! i (+ i 4)) 'null)
+            (else (parse-number)))))
+  (define (parse-object)
+    (if (char=? (next-char) #\})
+        (begin (set! i (+ i 1)) (list 'object))
+        (let loop ((members '()))
+          (expect #\")
+          (let ((key (parse-string)))
+            (expect #\:)
+            (let ((members (cons (cons key (parse-value)) members)))
+              (if (char=? (next-char) #\,)
+                  (begin (set! i (+ i 1)) (loop members))
+                  (begin (expect #\}) (cons 'object (reverse members)))))))))
+  (define (parse-array)
+    (if (char=? (next-char) #\])
+        (begin (set! i (+ i 1)) (list 'array))
+        (let loop ((items (list (parse-value))))
+          (if (char=? (next-char) #\,)
+              (begin (set! i (+ i 1)) (loop (cons (parse-value) items)))
+              (begin (expect #\]) (cons 'array (reverse items)))))))
+  (define (parse-string)
+    (let loop ((chars '()))
+      (when (>= i n) (error "da: unterminated string in JSON"))
+      (let ((c (string-ref text i)))
+        (set! i (+ i 1))
+        (cond ((char=? c #\") (list->string (reverse chars)))
+              ((char=? c #\\)
+               (let ((e (string-ref text i)))
+                 (set! i (+ i 1))
+                 (case e
+                   ((#\n) (loop (cons #\newline chars)))
+                   ((#\t) (loop (cons #\tab chars)))
+                   ((#\r) (loop (cons #\return chars)))
+                   ((#\b) (loop (cons #\backspace chars)))
+                   ((#\f) (loop (cons (integer->char 12) chars)))
+                   ((#\u)
+                    (let ((code (string->number (substring text i (+ i 4)) 16)))
+                      (set! i (+ i 4))
+                      (loop (cons (integer->char code) chars))))
+                   (else (loop (cons e chars))))))
+              (else (loop (cons c chars)))))))
+  (define (parse-number)
+    (let ((start i))
+      (let loop ()
+        (when (and (< i n) (memv (string-ref text i) '(#\- #\+ #\. #\e #\E #\0 #\1 #\2 #\3 #\4 #\5 #\6 #\7 #\8 #\9)))
+          (set! i (+ i 1))
+          (loop)))
+      (or (string->number (substring text start i))
+          (error "da: invalid JSON value at" start))))
+  (let ((document (parse-value)))
+    (skip-space)
+    (if (< i n) (error "da: trailing characters after JSON value at" i) document)))
+
+(define (da-json-array? node) (and (pair? node) (eq? (car node) 'array)))
+(define (da-json-object? node) (and (pair? node) (eq? (car node) 'object)))
+
+;; "$.items[*].price" -> ("items" "*" "price"), the supported subset is $ .name ['name'] [n] [*] .*
+(define (da-json-path-steps path)
+  (define n (string-length path))
+  (define (unquote-step step)
+    (let loop ((start 0) (end (string-length step)))
+      (cond ((and (< start end) (memv (string-ref step start) '(#\' #\"))) (loop (+ start 1) end))
+            ((and (< start end) (memv (string-ref step (- end 1)) '(#\' #\"))) (loop start (- end 1)))
+            (else (substring step start end)))))
+  (unless (and (> n 0) (char=? (string-ref path 0) #\$))
+    (error "da: a JSON path has to start with $" path))
+  (let loop ((i 1) (steps '()))
+    (cond ((>= i n) (reverse steps))
+          ((char=? (string-ref path i) #\.)
+           (let find ((j (+ i 1)))
+             (if (or (= j n) (memv (string-ref path j) '(#\. #\[)))
+                 (loop j (cons (substring path (+ i 1) j) steps))
+                 (find (+ j 1)))))
+          ((char=? (string-ref path i) #\[)
+           (let find ((j (+ i 1)))
+             (cond ((= j n) (error "da: unclosed [ in JSON path" path))
+                   ((char=? (string-ref path j) #\])
+                    (loop (+ j 1) (cons (unquote-step (substring path (+ i 1) j)) steps)))
+                   (else (find (+ j 1))))))
+          (else (error "da: unexpected character in JSON path" path)))))
+
+(define (da-json-step node step)
+  (let ((index (string->number step)))
+    (cond ((string=? step "*")
+           (cond ((da-json-array? node) (cdr node))
+                 ((da-json-object? node) (map cdr (cdr node)))
+                 (else '())))
+          ((and (da-json-array? node) index (exact-integer? index))
+           (if (and (>= index 0) (< index (length (cdr node)))) (list (list-ref (cdr node) index)) '()))
+          ((da-json-object? node)
+           (let ((member (assoc step (cdr node))))
+             (if member (list (cdr member)) '())))
+          (else '()))))
+
+(define (read-json file path . options)
+  (let* ((options (if (null? options) '() (car options)))
+         (rows (da-option options 'rows #f))
+         (skip-missing (da-option options 'skip-missing #f))
+         (policy (da-option options 'missing #f))
+         (nodes (let loop ((nodes (list (da-parse-json (da-read-file file))))
+                           (steps (da-json-path-steps path)))
+                  (if (null? steps)
+                      nodes
+                      (loop (apply append (map (lambda (node) (da-json-step node (car steps))) nodes))
+                            (cdr steps))))))
+    (define (number node)
+      (if (number? node) node (error "da: not a number" file path node)))
+    (let loop ((nodes nodes) (index 0) (result '()))
+      (if (null? nodes)
+          (reverse result)
+          (let ((node (car nodes)))
+            (cond ((not (da-in-rows? rows index)) (loop (cdr nodes) (+ index 1) result))
+                  ((eq? node 'null)
+                   (if skip-missing
+                       (loop (cdr nodes) (+ index 1) result)
+                       (loop (cdr nodes) (+ index 1) (cons (da-missing-value policy (list file path index)) result))))
+                  ;; A path that ends on an array of numbers selects all of them
+                  ((da-json-array? node)
+                   (loop (cdr nodes) (+ index 1) (append (reverse (map number (cdr node))) result)))
+                  (else (loop (cdr nodes) (+ index 1) (cons (number node) result)))))))))
+
+;; ---------------------------------------------------------------- statistics
+
+;; Statistics leave out dropped values
+(define (da-present x)
+  (let loop ((x x) (kept '()))
+    (cond ((null? x) (reverse kept))
+          ((nan? (car x)) (loop (cdr x) kept))
+          (else (loop (cdr x) (cons (car x) kept))))))
+
+;; Pairwise functions keep only the pairs where both values are present
+(define (da-complete-pairs x y)
+  (unless (= (length x) (length y))
+    (error "da: pairwise functions need vectors of the same length" (length x) (length y)))
+  (let loop ((x x) (y y) (xs '()) (ys '()))
+    (cond ((null? x) (values (reverse xs) (reverse ys)))
+          ((or (nan? (car x)) (nan? (car y))) (loop (cdr x) (cdr y) xs ys))
+          (else (loop (cdr x) (cdr y) (cons (car x) xs) (cons (car y) ys))))))
+
+(define (mean x)
+  (let ((x (da-present x)))
+    (when (null? x) (error "da: mean of an empty vector"))
+    (inexact (/ (apply + x) (length x)))))
+
+;; Sample standard deviation
+(define (stddev x)
+  (let ((x (da-present x)))
+    (when (< (length x) 2) (error "da: stddev needs at least two values"))
+    (let ((m (mean x)))
+      (sqrt (/ (apply + (map (lambda (v) (* (- v m) (- v m))) x)) (- (length x) 1))))))
+
+;; (sxx syy sxy) around the means
+(define (da-sums-of-squares x y)
+  (let ((mx (mean x)) (my (mean y)))
+    (list (apply + (map (lambda (a) (* (- a mx) (- a mx))) x))
+          (apply + (map (lambda (b) (* (- b my) (- b my))) y))
+          (apply + (map (lambda (a b) (* (- a mx) (- b my))) x y)))))
+
+;; Pearson correlation coefficient
+(define (correlation x y)
+  (let-values (((x y) (da-complete-pairs x y)))
+    (let ((sums (da-sums-of-squares x y)))
+      (/ (list-ref sums 2) (sqrt (* (car sums) (cadr sums)))))))
+
+;; Slope b of the least squares line y = a + bx
+(define (regressionb x y)
+  (let-values (((x y) (da-complete-pairs x y)))
+    (let ((sums (da-sums-of-squares x y)))
+      (/ (list-ref sums 2) (car sums)))))
+
+;; Intercept a of the least squares line y = a + bx
+(define (regressiona x y)
+  (let-values (((x y) (da-complete-pairs x y)))
+    (- (mean y) (* (regressionb x y) (mean x)))))
+
+;; ---------------------------------------------------------------- write
+
+;; Saves named values to a file, as a JSON object when the file ends in .json and as CSV otherwise
+;; -> (write-results "results.csv" (list (cons "a" a) (cons "x" x)))
+(define (write-results file pairs)
+  (when (file-exists? file) (delete-file file))
+  (call-with-output-file file
+    (lambda (port)
+      (if (da-suffix? (string-downcase file) ".json")
+          (da-write-json pairs port)
+          (da-write-csv pairs port)))))
+
+(define (da-write-json pairs port)
+  (define (json-number n) (if (nan? n) "null" (number->string n)))
+  (write-string "{\n" port)
+  (let loop ((pairs pairs))
+    (unless (null? pairs)
+      (write-string "  " port)
+      (write (car (car pairs)) port)
+      (write-string ": " port)
+      (let ((value (cdr (car pairs))))
+        (if (list? value)
+            (begin
+              (write-string "[" port)
+              (let items ((value value) (first #t))
+                (unless (null? value)
+                  (unless first (write-string ", " port))
+                  (write-string (json-number (car value)) port)
+                  (items (cdr value) #f)))
+              (write-string "]" port))
+            (write-string (json-number value) port)))
+      (unless (null? (cdr pairs)) (write-string "," port))
+      (newline port)
+      (loop (cdr pairs))))
+  (write-string "}\n" port))
+
+;; One column per name, a number only fills the first row and a vector fills as many rows as it has values
+(define (da-write-csv pairs port)
+  (define (join cells)
+    (apply string-append (car cells) (map (lambda (c) (string-append "," c)) (cdr cells))))
+  (define (cell value row)
+    (cond ((list? value)
+           (if (and (< row (length value)) (not (nan? (list-ref value row))))
+               (number->string (list-ref value row))
+               ""))
+          ((= row 0) (number->string value))
+          (else "")))
+  (let ((rows (apply max 0 (map (lambda (pair) (if (list? (cdr pair)) (length (cdr pair)) 1)) pairs))))
+    (write-string (join (map car pairs)) port)
+    (newline port)
+    (let loop ((row 0))
+      (when (< row rows)
+        (write-string (join (map (lambda (pair) (cell (cdr pair) row)) pairs)) port)
+        (newline port)
+        (loop (+ row 1))))))
+
+;; ---------------------------------------------------------------- program
@@ -0,0 +1,75 @@
+use std::fs;
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use da::paths::PathBase;
+use da::Store;
+
+use crate::batch;
+use crate::cli::{self, Command};
+
+// How often the watched files are looked at
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+// Runs the command, then again every time the DA file or one of the files it reads changes, until interrupted
+pub fn watch(command: Command) -> i32 {
+    let (file, paths) = match &command {
+        Command::Check { file, paths, .. } | Command::Run { file, paths, .. } => (file.clone(), paths.clone()),
+        Command::Build { files, paths, .. } => (files[0].clone(), paths.clone()),
+        _ => unreachable!("only check, build and run can be watched"),
+    };
+    if batch::is_batch(std::slice::from_ref(&file)) {
+        eprintln!("error: --watch needs a single input file");
+        return cli::EXIT_USAGE;
+    }
+
+    loop {
+        crate::execute(command.clone());
+
+        // The program may have changed what it reads, so the list is made again after every run
+        let watched = watched_files(&file, &paths);
+        let before = modified(&watched);
+        eprintln!("Watching {} for changes...", watched.join(", "));
+        loop {
+            thread::sleep(POLL_INTERVAL);
+            let now = modified(&watched);
+            if now != before {
+                let changed: Vec<&str> = watched
+                    .iter()
+                    .zip(before.iter().zip(&now))
+                    .filter(|(_, (before, now))| before != now)
+                    .map(|(path, _)| path.as_str())
+                    .collect();
+                eprintln!("\n{} changed, running again", changed.join(", "));
+                break;
+            }
+        }
+    }
+}
+
+// The DA file and every file its input ops read, as long as the program parses
+fn watched_files(file: &str, paths: &PathBase) -> Vec<String> {
+    let mut files = vec![file.to_string()];
+    if let Ok(source) = fs::read_to_string(file) {
+        // The files are resolved the same way the command resolves them
+        let mut store = Store::new(source, file.to_string());
+        store.set_paths(paths.clone());
+        if store.lex_and_parse_source().is_err() {
+            return files;
+        }
+        for input in &store.program().inputs {
+            if !files.contains(&input.file) {
+                files.push(input.file.clone());
+            }
+        }
+    }
+    files
+}
+
+// A file that does not exist has no time, so it counts as changed once it appears
+fn modified(files: &[String]) -> Vec<Option<SystemTime>> {
+    files
+        .iter()
+        .map(|file| fs::metadata(file).and_then(|metadata| metadata.modified()).ok())
+        .collect()
+}
@@ -0,0 +1,173 @@
+// Golden output of every code generator for the programs in tests/golden, and the output of each
+// generated program compared with da run wherever its engine is installed.
+// DA_BLESS=1 cargo test --test golden writes the current output as the new golden files, and
+// DA_ENGINES=all requires every engine to be installed
+
+use std::env;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use da::paths::PathBase;
+use da::Store;
+
+const PROGRAMS: [&str; 2] = ["stats", "json"];
+
+// Programs a target rejects on purpose, see the table of targets in the README: SQL, Julia and
+// Octave have no read_json
+const UNSUPPORTED: [(&str, &str); 3] = [("json", "sql"), ("json", "julia"), ("json", "octave")];
+
+// The targets that build the program, after checking that the others reject it
+fn targets(store: &Store, program: &str) -> Vec<&'static str> {
+    da::TARGETS
+        .into_iter()
+        .filter(|target| {
+            let unsupported = UNSUPPORTED.contains(&(program, *target));
+            if unsupported {
+                assert!(store.generate(target).is_err(), "{} now builds {}, take it off UNSUPPORTED", target, program);
+            }
+            !unsupported
+        })
+        .collect()
+}
+
+fn golden_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("golden")
+}
+
+// The program parsed with its file names relative to tests/golden, which the generated code then
+// refers to from the crate root
+fn store(program: &str) -> Store {
+    let file = format!("tests/golden/{}.da", program);
+    let source = fs::read_to_string(golden_dir().join(format!("{}.da", program))).unwrap();
+    let mut store = Store::new(source, file);
+    store.set_paths(PathBase::Source);
+    store.lex_and_parse_source().unwrap();
+    store
+}
+
+fn check_golden(path: &Path, actual: &str) {
+    if env::var_os("DA_BLESS").is_some() {
+        fs::write(path, actual).unwrap();
+        return;
+    }
+    let expected = fs::read_to_string(path).unwrap_or_else(|e| panic!("{}: {}", path.display(), e));
+    assert!(expected == actual, "{} differs from the generated code:\n{}", path.display(), actual);
+}
+
+#[test]
+fn generated_code_matches_the_golden_files() {
+    for program in PROGRAMS {
+        let store = store(program);
+        for target in targets(&store, program) {
+            let code = store.generate(target).unwrap();
+            let path = golden_dir().join(format!("{}.{}", program, da::extension(target).unwrap()));
+            check_golden(&path, &code);
+        }
+    }
+}
+
+#[test]
+fn da_run_matches_the_expected_output() {
+    for program in PROGRAMS {
+        let output = Command::new(env!("CARGO_BIN_EXE_da"))
+            .args(["run", "--relative-to", "source", &format!("tests/golden/{}.da", program)])
+            .current_dir(env!("CARGO_MANIFEST_DIR"))
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        check_golden(&golden_dir().join(format!("{}.out", program)), &String::from_utf8(output.stdout).unwrap());
+    }
+}
+
+// The lines of two outputs agree when they are the same text or numbers within the precision the
+// engine prints, Octave shows only 5 significant digits
+fn same_output(actual: &str, expected: &str) -> bool {
+    let actual: Vec<&str> = actual.lines().map(str::trim).filter(|line| !line.is_empty()).collect();
+    let expected: Vec<&str> = expected.lines().collect();
+    actual.len() == expected.len()
+        && actual.iter().zip(&expected).all(|(a, e)| match (a.parse::<f64>(), e.parse::<f64>()) {
+            (Ok(a), Ok(e)) => (a - e).abs() <= 1e-4 * e.abs().max(1.0),
+            _ => a == e,
+        })
+}
+
+// Runs a command from the crate root, None when its program is not installed. With DA_ENGINES=all set,
+// as on a machine that has every engine, a missing one fails the test instead of being skipped
+fn engine(program: &str, args: &[&str], stdin: Option<&Path>) -> Option<String> {
+    let mut command = Command::new(program);
+    command.args(args).current_dir(env!("CARGO_MANIFEST_DIR"));
+    if let Some(path) = stdin {
+        command.stdin(fs::File::open(path).unwrap());
+    }
+    let output = match command.output() {
+        Ok(output) => output,
+        Err(e) if e.kind() == ErrorKind::NotFound && env::var("DA_ENGINES").as_deref() != Ok("all") => {
+            eprintln!("{} is not installed, skipped", program);
+            return None;
+        }
+        Err(e) => panic!("{}: {}", program, e),
+    };
+    assert!(output.status.success(), "{} failed: {}", program, String::from_utf8_lossy(&output.stderr));
+    Some(String::from_utf8(output.stdout).unwrap())
+}
+
+// A table named after the CSV file of the program, with NULL for its missing cells, followed by the query
+fn sqlite_script(csv: &Path, query: &str) -> String {
+    let text = fs::read_to_string(csv).unwrap();
+    let mut lines = text.lines();
+    let table = csv.file_stem().unwrap().to_string_lossy();
+    let columns: Vec<String> = lines.next().unwrap().split(',').map(|name| format!("{} REAL", name)).collect();
+    let mut script = format!("CREATE TABLE {} ({});\n", table, columns.join(", "));
+    for line in lines {
+        let cells: Vec<&str> = line.split(',').map(|cell| if cell == "NA" { "NULL" } else { cell }).collect();
+        script.push_str(&format!("INSERT INTO {} VALUES ({});\n", table, cells.join(", ")));
+    }
+    script.push_str(query);
+    script
+}
+
+#[test]
+fn generated_programs_print_what_da_run_prints() {
+    let scratch = env::temp_dir().join(format!("da-golden-{}", std::process::id()));
+    fs::create_dir_all(&scratch).unwrap();
+    for program in PROGRAMS {
+        let store = store(program);
+        let expected = fs::read_to_string(golden_dir().join(format!("{}.out", program))).unwrap();
+        for target in targets(&store, program) {
+            let code = match store.generate_standalone(target) {
+                Ok(code) => code,
+                Err(_) => store.generate(target).unwrap(),
+            };
+            let path = scratch.join(format!("{}.{}", program, da::extension(target).unwrap()));
+            fs::write(&path, &code).unwrap();
+            let file = path.to_str().unwrap();
+            let mut wanted = expected.clone();
+            let output = match target {
+                "scheme" => engine("chibi-scheme", &[file], None),
+                "prolog" => engine("swipl", &[file], None),
+                "javascript" => engine("node", &[file], None),
+                "julia" => engine("julia", &[file], None),
+                "octave" => engine("octave", &["--quiet", "--no-gui", file], None),
+                "rust" => {
+                    let binary = scratch.join(program);
+                    engine("rustc", &["-O", "-o", binary.to_str().unwrap(), file], None)
+                        .and_then(|_| engine(binary.to_str().unwrap(), &[], None))
+                }
+                // The query only computes the numbers, one column each, and leaves out the text lines
+                "sql" => {
+                    let csv = golden_dir().join(format!("{}.csv", program));
+                    fs::write(&path, sqlite_script(&csv, &code)).unwrap();
+                    wanted = expected.lines().filter(|line| line.parse::<f64>().is_ok()).collect::<Vec<_>>().join("\n");
+                    engine("sqlite3", &[":memory:"], Some(&path)).map(|row| row.trim_end().replace('|', "\n"))
+                }
+                other => panic!("no engine for {}", other),
+            };
+            if let Some(output) = output {
+                assert!(same_output(&output, &wanted), "{} printed\n{}\ninstead of\n{}", target, output, wanted);
+            }
+        }
+    }
+    fs::remove_dir_all(scratch).unwrap();
+}
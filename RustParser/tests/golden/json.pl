@@ -0,0 +1,12 @@
+/* Processing input file tests/golden/json.da
+   Lexical and Syntax analysis passed */
+
+  main :-
+	load_json_values('tests/golden/json.json', "$.items[*].price", [missing(drop)], Data0),
+	load_json_values('tests/golden/json.json', "$.items[*].qty", [missing(drop)], Data1),
+	mean(Data0, M),
+	correlation(Data0, Data1, R),
+	writeln("mean price"),
+	writeln(M),
+	writeln("correlation"),
+	writeln(R).
@@ -0,0 +1,545 @@
+// Processing input file tests/golden/json.da
+// Lexical and Syntax analysis passed
+
+#![allow(dead_code, unused_variables, non_snake_case)]
+
+// Runtime of the DA Rust target, every generated main.rs contains it. It only uses the standard
+// library, so rustc -O main.rs builds the program and so does cargo build in a crate around it
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::process;
+
+// ---- Reading ----
+
+// What happens to a blank, NA or null cell, an error unless a read or the program says otherwise
+#[derive(Clone, Copy)]
+enum Missing {
+    Drop,
+    Zero,
+    Error,
+}
+
+// The named arguments of read(...), with their escapes already resolved
+#[derive(Default)]
+struct ReadOptions {
+    delimiter: Option<&'static str>,
+    quote: Option<&'static str>,
+    encoding: Option<&'static str>,
+    rows: Option<(usize, Option<usize>)>, // (10, Some(500)) keeps the data rows 10 up to but not including 500
+    skip_missing: bool,
+    missing: Option<Missing>,
+}
+
+impl ReadOptions {
+    fn keeps(&self, row: usize) -> bool {
+        match self.rows {
+            Some((start, end)) => row >= start && end.is_none_or(|end| row < end),
+            None => true,
+        }
+    }
+}
+
+// Turns the raw bytes of a file into text, utf-8 is assumed unless told otherwise
+fn decode(file: &str, encoding: Option<&str>) -> Result<String, String> {
+    let bytes = fs::read(file).map_err(|e| format!("Could not read {}: {}", file, e))?;
+    match encoding.map(|e| e.to_lowercase()).as_deref() {
+        None | Some("utf-8") | Some("utf8") => Ok(String::from_utf8_lossy(&bytes).into_owned()),
+        Some("latin1") | Some("latin-1") | Some("iso-8859-1") => Ok(bytes.iter().map(|&b| b as char).collect()),
+        Some(other) => Err(format!("Unsupported encoding {}, use utf-8 or latin1", other)),
+    }
+}
+
+fn single_char(option: Option<&str>, default: char, name: &str) -> Result<char, String> {
+    let Some(value) = option else { return Ok(default) };
+    let mut chars = value.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Ok(c),
+        _ => Err(format!("The {} must be a single character, found {:?}", name, value)),
+    }
+}
+
+// Splits text into records and fields, a quoted field may contain the delimiter, newlines
+// and doubled quote characters
+fn split_records(text: &str, delimiter: char, quote: char) -> Vec<Vec<String>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quote = false;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quote {
+            if c == quote && chars.peek() == Some(&quote) {
+                field.push(quote);
+                chars.next();
+            } else if c == quote {
+                in_quote = false;
+            } else {
+                field.push(c);
+            }
+        } else if c == quote {
+            in_quote = true;
+        } else if c == delimiter {
+            record.push(std::mem::take(&mut field));
+        } else if c == '\n' || c == '\r' {
+            if c == '\r' && chars.peek() == Some(&'\n') {
+                chars.next();
+            }
+            record.push(std::mem::take(&mut field));
+            // Blank lines are skipped
+            if !(record.len() == 1 && record[0].is_empty()) {
+                records.push(record.clone());
+            }
+            record.clear();
+        } else {
+            field.push(c);
+        }
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    records
+}
+
+// Blank cells and the usual spellings of "not available" are missing values
+fn is_missing(field: &str) -> bool {
+    matches!(field.to_lowercase().as_str(), "" | "na" | "n/a" | "nan" | "null")
+}
+
+// A dropped value is kept as NaN so vectors read from the same file stay aligned
+fn missing_value(options: &ReadOptions, place: &str) -> Result<f64, String> {
+    match options.missing {
+        Some(Missing::Drop) => Ok(f64::NAN),
+        Some(Missing::Zero) => Ok(0.0),
+        Some(Missing::Error) | None => {
+            Err(format!("{} is missing, use missing = drop or missing = zero to allow missing values", place))
+        }
+    }
+}
+
+// One column of a delimited file, the first row is skipped when it is a header
+fn read_csv(file: &str, header: bool, column: usize, options: &ReadOptions) -> Result<Vec<f64>, String> {
+    let text = decode(file, options.encoding)?;
+    let delimiter = single_char(options.delimiter, ',', "delimiter")?;
+    let quote = single_char(options.quote, '"', "quote")?;
+    let mut values = Vec::new();
+    for (row, record) in split_records(&text, delimiter, quote).iter().enumerate() {
+        if header && row == 0 {
+            continue;
+        }
+        if !options.keeps(if header { row - 1 } else { row }) {
+            continue;
+        }
+        // A short row counts as a missing cell
+        let field = record.get(column).map(|field| field.trim()).unwrap_or("");
+        if is_missing(field) {
+            if !options.skip_missing {
+                values.push(missing_value(options, &format!("{} row {} column {}", file, row + 1, column))?);
+            }
+            continue;
+        }
+        match field.parse::<f64>() {
+            Ok(value) => values.push(value),
+            Err(_) => return Err(format!("{} row {} column {}: {:?} is not a number", file, row + 1, column, field)),
+        }
+    }
+    Ok(values)
+}
+
+fn read_tsv(file: &str, header: bool, column: usize, options: &ReadOptions) -> Result<Vec<f64>, String> {
+    read_csv(file, header, column, &ReadOptions { delimiter: Some("\t"), ..*options })
+}
+
+// A parsed JSON document, objects keep their keys sorted like serde_json does by default
+enum Json {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    Object(BTreeMap<String, Json>),
+}
+
+struct JsonParser {
+    chars: Vec<char>,
+    at: usize,
+}
+
+impl JsonParser {
+    fn parse(text: &str) -> Result<Json, String> {
+        let mut parser = JsonParser { chars: text.chars().collect(), at: 0 };
+        let value = parser.value()?;
+        parser.space();
+        match parser.chars.get(parser.at) {
+            None => Ok(value),
+            Some(c) => Err(format!("unexpected {:?} after the value", c)),
+        }
+    }
+
+    fn space(&mut self) {
+        while self.chars.get(self.at).is_some_and(|c| c.is_whitespace()) {
+            self.at += 1;
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), String> {
+        self.space();
+        if self.chars.get(self.at) == Some(&c) {
+            self.at += 1;
+            Ok(())
+        } else {
+            Err(format!("expected {:?} at character {}", c, self.at))
+        }
+    }
+
+    fn value(&mut self) -> Result<Json, String> {
+        self.space();
+        match self.chars.get(self.at) {
+            Some('{') => {
+                self.at += 1;
+                let mut object = BTreeMap::new();
+                self.space();
+                if self.chars.get(self.at) == Some(&'}') {
+                    self.at += 1;
+                    return Ok(Json::Object(object));
+                }
+                loop {
+                    self.space();
+                    let key = self.string()?;
+                    self.expect(':')?;
+                    object.insert(key, self.value()?);
+                    self.space();
+                    match self.chars.get(self.at) {
+                        Some(',') => self.at += 1,
+                        _ => break,
+                    }
+                }
+                self.expect('}')?;
+                Ok(Json::Object(object))
+            }
+            Some('[') => {
+                self.at += 1;
+                let mut items = Vec::new();
+                self.space();
+                if self.chars.get(self.at) == Some(&']') {
+                    self.at += 1;
+                    return Ok(Json::Array(items));
+                }
+                loop {
+                    items.push(self.value()?);
+                    self.space();
+                    match self.chars.get(self.at) {
+                        Some(',') => self.at += 1,
+                        _ => break,
+                    }
+                }
+                self.expect(']')?;
+                Ok(Json::Array(items))
+            }
+            Some('"') => Ok(Json::String(self.string()?)),
+            Some(_) => {
+                let start = self.at;
+                while self.chars.get(self.at).is_some_and(|c| c.is_ascii_alphanumeric() || "+-.".contains(*c)) {
+                    self.at += 1;
+                }
+                let word: String = self.chars[start..self.at].iter().collect();
+                match word.as_str() {
+                    "null" => Ok(Json::Null),
+                    "true" => Ok(Json::Bool(true)),
+                    "false" => Ok(Json::Bool(false)),
+                    _ => word.parse().map(Json::Number).map_err(|_| format!("unexpected {:?} at character {}", word, start)),
+                }
+            }
+            None => Err("unexpected end of input".to_string()),
+        }
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        if self.chars.get(self.at) != Some(&'"') {
+            return Err(format!("expected a string at character {}", self.at));
+        }
+        self.at += 1;
+        let mut out = String::new();
+        loop {
+            let Some(&c) = self.chars.get(self.at) else { return Err("unterminated string".to_string()) };
+            self.at += 1;
+            match c {
+                '"' => return Ok(out),
+                '\\' => {
+                    let Some(&escape) = self.chars.get(self.at) else { return Err("unterminated string".to_string()) };
+                    self.at += 1;
+                    match escape {
+                        'n' => out.push('\n'),
+                        't' => out.push('\t'),
+                        'r' => out.push('\r'),
+                        'b' => out.push('\u{8}'),
+                        'f' => out.push('\u{c}'),
+                        'u' => {
+                            let code: String = self.chars.iter().skip(self.at).take(4).collect();
+                            let code = u32::from_str_radix(&code, 16).map_err(|_| format!("bad escape \\u{}", code))?;
+                            self.at += 4;
+                            out.push(char::from_u32(code).unwrap_or('\u{fffd}'));
+                        }
+                        other => out.push(other),
+                    }
+                }
+                c => out.push(c),
+            }
+        }
+    }
+}
+
+// Evaluates the supported subset of JSON path -> $ .name ['name'] [n] [*] .*
+fn select<'a>(document: &'a Json, path: &str) -> Result<Vec<&'a Json>, String> {
+    if !path.starts_with('$') {
+        return Err(format!("A JSON path has to start with $, found {}", path));
+    }
+    let chars: Vec<char> = path.chars().collect();
+    let mut nodes = vec![document];
+    let mut i = 1;
+    while i < chars.len() {
+        let step: String;
+        if chars[i] == '.' {
+            let start = i + 1;
+            i = start;
+            while i < chars.len() && chars[i] != '.' && chars[i] != '[' {
+                i += 1;
+            }
+            step = chars[start..i].iter().collect();
+        } else if chars[i] == '[' {
+            let start = i + 1;
+            while i < chars.len() && chars[i] != ']' {
+                i += 1;
+            }
+            if i == chars.len() {
+                return Err(format!("Unclosed [ in JSON path {}", path));
+            }
+            step = chars[start..i].iter().collect::<String>().trim_matches(|c| c == '\'' || c == '"').to_string();
+            i += 1;
+        } else {
+            return Err(format!("Unexpected {} in JSON path {}", chars[i], path));
+        }
+
+        let mut next = Vec::new();
+        for node in nodes {
+            match (node, step.as_str()) {
+                (Json::Array(items), "*") => next.extend(items.iter()),
+                (Json::Object(map), "*") => next.extend(map.values()),
+                (Json::Array(items), step) => next.extend(step.parse::<usize>().ok().and_then(|index| items.get(index))),
+                (Json::Object(map), step) => next.extend(map.get(step)),
+                _ => {}
+            }
+        }
+        nodes = next;
+    }
+    Ok(nodes)
+}
+
+fn json_number(file: &str, path: &str, node: &Json) -> Result<f64, String> {
+    match node {
+        Json::Number(value) => Ok(*value),
+        _ => Err(format!("{} {}: the value is not a number", file, path)),
+    }
+}
+
+// Every number selected by a JSON path such as $.items[*].price
+fn read_json(file: &str, path: &str, options: &ReadOptions) -> Result<Vec<f64>, String> {
+    let text = decode(file, options.encoding)?;
+    let document = JsonParser::parse(&text).map_err(|e| format!("{} is not valid JSON: {}", file, e))?;
+    let mut values = Vec::new();
+    for (index, node) in select(&document, path)?.into_iter().enumerate() {
+        if !options.keeps(index) {
+            continue;
+        }
+        match node {
+            Json::Null => {
+                if !options.skip_missing {
+                    values.push(missing_value(options, &format!("{} {} value {}", file, path, index))?);
+                }
+            }
+            // A path that ends on an array of numbers selects all of them
+            Json::Array(items) => {
+                for item in items {
+                    values.push(json_number(file, path, item)?);
+                }
+            }
+            other => values.push(json_number(file, path, other)?),
+        }
+    }
+    Ok(values)
+}
+
+// ---- Statistics ----
+
+// Missing values dropped while reading are NaN, every statistic leaves them out
+fn present(x: &[f64]) -> Vec<f64> {
+    x.iter().copied().filter(|v| !v.is_nan()).collect()
+}
+
+// Pairwise functions keep only the pairs where both values are present
+fn complete_pairs(x: &[f64], y: &[f64]) -> Result<(Vec<f64>, Vec<f64>), String> {
+    if x.len() != y.len() {
+        return Err(format!("Pairwise functions need vectors of the same length, found {} and {}", x.len(), y.len()));
+    }
+    Ok(x.iter().zip(y).filter(|(a, b)| !a.is_nan() && !b.is_nan()).map(|(a, b)| (*a, *b)).unzip())
+}
+
+fn mean(x: &[f64]) -> Result<f64, String> {
+    let x = present(x);
+    if x.is_empty() {
+        return Err("mean of an empty vector".to_string());
+    }
+    Ok(x.iter().sum::<f64>() / x.len() as f64)
+}
+
+// Sample standard deviation
+fn stddev(x: &[f64]) -> Result<f64, String> {
+    let x = present(x);
+    if x.len() < 2 {
+        return Err("stddev needs at least two values".to_string());
+    }
+    let m = mean(&x)?;
+    Ok((x.iter().map(|v| (v - m) * (v - m)).sum::<f64>() / (x.len() - 1) as f64).sqrt())
+}
+
+fn sums_of_squares(x: &[f64], y: &[f64]) -> Result<(f64, f64, f64), String> {
+    let (mx, my) = (mean(x)?, mean(y)?);
+    let mut sums = (0.0, 0.0, 0.0);
+    for (a, b) in x.iter().zip(y) {
+        sums.0 += (a - mx) * (a - mx);
+        sums.1 += (b - my) * (b - my);
+        sums.2 += (a - mx) * (b - my);
+    }
+    Ok(sums)
+}
+
+// Pearson correlation coefficient
+fn correlation(x: &[f64], y: &[f64]) -> Result<f64, String> {
+    let (x, y) = complete_pairs(x, y)?;
+    let (sxx, syy, sxy) = sums_of_squares(&x, &y)?;
+    Ok(sxy / (sxx * syy).sqrt())
+}
+
+// Intercept a of the least squares line y = a + bx
+fn regressiona(x: &[f64], y: &[f64]) -> Result<f64, String> {
+    let (x, y) = complete_pairs(x, y)?;
+    Ok(mean(&y)? - regressionb(&x, &y)? * mean(&x)?)
+}
+
+// Slope b of the least squares line y = a + bx
+fn regressionb(x: &[f64], y: &[f64]) -> Result<f64, String> {
+    let (x, y) = complete_pairs(x, y)?;
+    let (sxx, _, sxy) = sums_of_squares(&x, &y)?;
+    Ok(sxy / sxx)
+}
+
+// ---- Output ----
+
+// A number or a vector, shown and saved the way da run does
+trait Value {
+    fn show(&self) -> String;
+    fn json(&self, indent: &str) -> String;
+    fn cell(&self, row: usize) -> String;
+    fn rows(&self) -> usize;
+}
+
+impl Value for f64 {
+    fn show(&self) -> String {
+        self.to_string()
+    }
+
+    fn json(&self, _: &str) -> String {
+        if self.is_finite() { format!("{:?}", self) } else { "null".to_string() }
+    }
+
+    fn cell(&self, row: usize) -> String {
+        if row == 0 { self.to_string() } else { String::new() }
+    }
+
+    fn rows(&self) -> usize {
+        1
+    }
+}
+
+impl Value for Vec<f64> {
+    // A vector is shown the way Scheme displays a list -> (1 2 3)
+    fn show(&self) -> String {
+        let items: Vec<String> = self.iter().map(|v| v.to_string()).collect();
+        format!("({})", items.join(" "))
+    }
+
+    fn json(&self, indent: &str) -> String {
+        if self.is_empty() {
+            return "[]".to_string();
+        }
+        let items: Vec<String> = self.iter().map(|v| format!("{}  {}", indent, v.json(indent))).collect();
+        format!("[\n{}\n{}]", items.join(",\n"), indent)
+    }
+
+    fn cell(&self, row: usize) -> String {
+        match self.get(row) {
+            Some(v) if !v.is_nan() => v.to_string(),
+            _ => String::new(),
+        }
+    }
+
+    fn rows(&self) -> usize {
+        self.len()
+    }
+}
+
+fn json_string(text: &str) -> String {
+    let mut out = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if c.is_control() => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+// Saves named values, as a JSON object when the file ends in .json and as CSV otherwise
+// In the CSV a number only fills the first row and a vector as many rows as it has values
+fn write_results(file: &str, values: &[(&str, &dyn Value)]) -> Result<(), String> {
+    let text = if file.to_lowercase().ends_with(".json") {
+        let fields: Vec<String> =
+            values.iter().map(|(name, value)| format!("  {}: {}", json_string(name), value.json("  "))).collect();
+        format!("{{\n{}\n}}\n", fields.join(",\n"))
+    } else {
+        let rows = values.iter().map(|(_, value)| value.rows()).max().unwrap_or(0);
+        let mut lines = vec![values.iter().map(|(name, _)| *name).collect::<Vec<_>>().join(",")];
+        for row in 0..rows {
+            lines.push(values.iter().map(|(_, value)| value.cell(row)).collect::<Vec<_>>().join(","));
+        }
+        lines.join("\n") + "\n"
+    };
+    fs::write(file, text).map_err(|e| format!("Could not write {}: {}", file, e))
+}
+
+// Only the message of an error is reported, with exit code 1
+fn main() {
+    if let Err(message) = run() {
+        eprintln!("error: {}", message);
+        process::exit(1);
+    }
+}
+
+// ---- Program ----
+
+fn run() -> Result<(), String> {
+    let price = read_json("tests/golden/json.json", "$.items[*].price", &ReadOptions { missing: Some(Missing::Drop), ..ReadOptions::default() })?;
+    let qty = read_json("tests/golden/json.json", "$.items[*].qty", &ReadOptions { missing: Some(Missing::Drop), ..ReadOptions::default() })?;
+    let m = mean(&price)?;
+    let r = correlation(&price, &qty)?;
+    println!("mean price");
+    println!("{}", m.show());
+    println!("correlation");
+    println!("{}", r.show());
+    Ok(())
+}
@@ -0,0 +1,14 @@
+; Processing Input File tests/golden/json.da
+; Lexical and Syntax analysis passed
+(define price (read-json "tests/golden/json.json" "$.items[*].price" '((missing . drop))))
+(define qty (read-json "tests/golden/json.json" "$.items[*].qty" '((missing . drop))))
+(define m (mean price))
+(define r (correlation price qty))
+(display "mean price")
+(newline)
+(display m)
+(newline)
+(display "correlation")
+(newline)
+(display r)
+(newline)
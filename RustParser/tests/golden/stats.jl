@@ -0,0 +1,129 @@
+# Processing input file tests/golden/stats.da
+# Lexical and Syntax analysis passed
+
+# Runtime of the DA Julia target, every generated script contains it
+# Needs the CSV package -> julia -e 'using Pkg; Pkg.add("CSV")'
+
+using CSV
+using Statistics
+
+# ---- Reading ----
+
+# Blank cells and the usual spellings of "not available" are missing values
+const DA_MISSING = ("", "na", "n/a", "nan", "null")
+
+# The value a missing cell is read as, a dropped cell is kept as NaN so vectors read from the
+# same file stay aligned until a statistic leaves it out
+function da_missing_value(policy, place)
+    policy === :drop && return NaN
+    policy === :zero && return 0.0
+    error("$place is missing, use missing = drop or missing = zero to allow missing values")
+end
+
+function da_single_char(value, name)
+    length(value) == 1 || error("The $name must be a single character, found $(repr(value))")
+    only(value)
+end
+
+function da_decode(file, encoding)
+    name = lowercase(encoding)
+    name in ("utf-8", "utf8") && return read(file, String)
+    name in ("latin1", "latin-1", "iso-8859-1") && return String(map(Char, read(file)))
+    error("Unsupported encoding $encoding, use utf-8 or latin1")
+end
+
+# One column of a delimited file, counted from 0 as in DA, the first row is skipped when it is a header
+# rows = (10, 500) keeps the data rows 10 up to but not including 500, (10, nothing) has no end
+function read_column(file, header, column; delimiter=",", quote_char="\"", encoding="utf-8", rows=nothing,
+                     skip_missing=false, policy=nothing)
+    quotechar = da_single_char(quote_char, "quote")
+    table = CSV.File(IOBuffer(da_decode(file, encoding)); header=false, delim=da_single_char(delimiter, "delimiter"),
+                     quotechar=quotechar, escapechar=quotechar, types=String, strict=false, silencewarnings=true)
+    values = Float64[]
+    for (i, row) in enumerate(table)
+        header && i == 1 && continue
+        data_row = header ? i - 2 : i - 1
+        if rows !== nothing && (data_row < rows[1] || (rows[2] !== nothing && data_row >= rows[2]))
+            continue
+        end
+        # A short row counts as a missing cell
+        cell = column < length(row) ? row[column + 1] : missing
+        text = ismissing(cell) ? "" : strip(cell)
+        if lowercase(text) in DA_MISSING
+            skip_missing || push!(values, da_missing_value(policy, "$file row $i column $column"))
+            continue
+        end
+        value = tryparse(Float64, text)
+        value === nothing && error("$file row $i column $column: $(repr(text)) is not a number")
+        push!(values, value)
+    end
+    values
+end
+
+read_tsv_column(file, header, column; options...) = read_column(file, header, column; options..., delimiter="\t")
+
+# ---- Statistics ----
+
+# Missing values dropped while reading are NaN, every statistic leaves them out
+present(x) = filter(!isnan, x)
+
+# Pairwise functions keep only the pairs where both values are present
+function complete_pairs(x, y)
+    length(x) == length(y) ||
+        error("Pairwise functions need vectors of the same length, found $(length(x)) and $(length(y))")
+    keep = .!isnan.(x) .& .!isnan.(y)
+    x[keep], y[keep]
+end
+
+# Slope b and intercept a of the least squares line y = a + bx
+slope(x, y) = cov(x, y) / var(x)
+intercept(x, y) = mean(y) - slope(x, y) * mean(x)
+
+# ---- Output ----
+
+da_json(value::Real) = isnan(value) ? "null" : repr(Float64(value))
+da_json(values::AbstractVector) = "[" * join(da_json.(values), ", ") * "]"
+
+# Saves named values, as a JSON object when the file ends in .json and as CSV otherwise
+# In the CSV a number only fills the first row and a vector as many rows as it has values
+function write_results(file, values)
+    if endswith(lowercase(file), ".json")
+        fields = ["  $(repr(name)): $(da_json(value))" for (name, value) in values]
+        text = "{\n" * join(fields, ",\n") * "\n}\n"
+    else
+        rows = maximum(value -> value isa AbstractVector ? length(value) : 1, last.(values); init=0)
+        lines = [join(first.(values), ",")]
+        for row in 1:rows
+            cells = map(last.(values)) do value
+                if value isa AbstractVector
+                    row <= length(value) && !isnan(value[row]) ? string(value[row]) : ""
+                else
+                    row == 1 ? string(value) : ""
+                end
+            end
+            push!(lines, join(cells, ","))
+        end
+        text = join(lines, "\n") * "\n"
+    end
+    write(file, text)
+    nothing
+end
+
+# ---- Program ----
+
+x = read_column("tests/golden/stats.csv", true, 0; policy=:drop)
+y = read_column("tests/golden/stats.csv", true, 1; policy=:drop)
+m = mean(present(x))
+s = std(present(y))
+r = cor(complete_pairs(x, y)...)
+a = intercept(complete_pairs(x, y)...)
+b = slope(complete_pairs(x, y)...)
+println("mean of x")
+println(m)
+println("stddev of y")
+println(s)
+println("correlation")
+println(r)
+println("line")
+println(a)
+println(b)
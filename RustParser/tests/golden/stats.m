@@ -0,0 +1,51 @@
+% Processing input file tests/golden/stats.da
+% Lexical and Syntax analysis passed
+
+da_lines = regexp(fileread('tests/golden/stats.csv'), '\r\n|\n|\r', 'split');
+da_lines = da_lines(~cellfun(@isempty, da_lines));
+da_lines = da_lines(2:end);
+da_delimiter = regexptranslate('escape', ',');
+x = NaN(numel(da_lines), 1);
+for da_row = 1:numel(da_lines)
+  da_cells = regexp(da_lines{da_row}, da_delimiter, 'split');
+  da_cell = '';
+  if numel(da_cells) >= 1, da_cell = strtrim(da_cells{1}); end
+  if ~any(strcmpi(da_cell, {'', 'na', 'n/a', 'nan', 'null'}))
+    x(da_row) = str2double(da_cell);
+    if isnan(x(da_row)), error('%s row %d column 0: %s is not a number', 'tests/golden/stats.csv', da_row + 1, da_cell); end
+  end
+end
+da_lines = regexp(fileread('tests/golden/stats.csv'), '\r\n|\n|\r', 'split');
+da_lines = da_lines(~cellfun(@isempty, da_lines));
+da_lines = da_lines(2:end);
+da_delimiter = regexptranslate('escape', ',');
+y = NaN(numel(da_lines), 1);
+for da_row = 1:numel(da_lines)
+  da_cells = regexp(da_lines{da_row}, da_delimiter, 'split');
+  da_cell = '';
+  if numel(da_cells) >= 2, da_cell = strtrim(da_cells{2}); end
+  if ~any(strcmpi(da_cell, {'', 'na', 'n/a', 'nan', 'null'}))
+    y(da_row) = str2double(da_cell);
+    if isnan(y(da_row)), error('%s row %d column 1: %s is not a number', 'tests/golden/stats.csv', da_row + 1, da_cell); end
+  end
+end
+m = mean(x(~isnan(x)));
+s = std(y(~isnan(y)));
+da_keep = ~isnan(x) & ~isnan(y);
+da_r = corrcoef(x(da_keep), y(da_keep));
+r = da_r(1, 2);
+da_keep = ~isnan(x) & ~isnan(y);
+da_fit = polyfit(x(da_keep), y(da_keep), 1);
+a = da_fit(2);
+da_keep = ~isnan(x) & ~isnan(y);
+da_fit = polyfit(x(da_keep), y(da_keep), 1);
+b = da_fit(1);
+disp('mean of x');
+disp(m);
+disp('stddev of y');
+disp(s);
+disp('correlation');
+disp(r);
+disp('line');
+disp(a);
+disp(b);
@@ -0,0 +1,20 @@
+/* Processing input file tests/golden/stats.da
+   Lexical and Syntax analysis passed */
+
+  main :-
+	load_data_column('tests/golden/stats.csv', true, 0, [missing(drop)], Data0),
+	load_data_column('tests/golden/stats.csv', true, 1, [missing(drop)], Data1),
+	mean(Data0, M),
+	stddev(Data1, S),
+	correlation(Data0, Data1, R),
+	regressiona(Data0, Data1, A),
+	regressionb(Data0, Data1, B),
+	writeln("mean of x"),
+	writeln(M),
+	writeln("stddev of y"),
+	writeln(S),
+	writeln("correlation"),
+	writeln(R),
+	writeln("line"),
+	writeln(A),
+	writeln(B).
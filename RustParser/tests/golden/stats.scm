@@ -0,0 +1,27 @@
+; Processing Input File tests/golden/stats.da
+; Lexical and Syntax analysis passed
+(define x (read-csv "tests/golden/stats.csv" #t 0 '((missing . drop))))
+(define y (read-csv "tests/golden/stats.csv" #t 1 '((missing . drop))))
+(define m (mean x))
+(define s (stddev y))
+(define r (correlation x y))
+(define a (regressiona x y))
+(define b (regressionb x y))
+(display "mean of x")
+(newline)
+(display m)
+(newline)
+(display "stddev of y")
+(newline)
+(display s)
+(newline)
+(display "correlation")
+(newline)
+(display r)
+(newline)
+(display "line")
+(newline)
+(display a)
+(newline)
+(display b)
+(newline)
@@ -0,0 +1,10 @@
+-- Processing input file tests/golden/stats.da
+-- Lexical and Syntax analysis passed
+
+SELECT
+   AVG(CAST(stats.x AS DOUBLE PRECISION)) AS m,
+   SQRT((SUM(CAST(stats.y AS DOUBLE PRECISION) * CAST(stats.y AS DOUBLE PRECISION)) - SUM(CAST(stats.y AS DOUBLE PRECISION)) * SUM(CAST(stats.y AS DOUBLE PRECISION)) / COUNT(CAST(stats.y AS DOUBLE PRECISION))) / (COUNT(CAST(stats.y AS DOUBLE PRECISION)) - 1)) AS s,
+   (SUM(CASE WHEN CAST(stats.y AS DOUBLE PRECISION) IS NOT NULL THEN CAST(stats.x AS DOUBLE PRECISION) END * CASE WHEN CAST(stats.x AS DOUBLE PRECISION) IS NOT NULL THEN CAST(stats.y AS DOUBLE PRECISION) END) - SUM(CASE WHEN CAST(stats.y AS DOUBLE PRECISION) IS NOT NULL THEN CAST(stats.x AS DOUBLE PRECISION) END) * SUM(CASE WHEN CAST(stats.x AS DOUBLE PRECISION) IS NOT NULL THEN CAST(stats.y AS DOUBLE PRECISION) END) / COUNT(CASE WHEN CAST(stats.y AS DOUBLE PRECISION) IS NOT NULL THEN CAST(stats.x AS DOUBLE PRECISION) END)) / SQRT((SUM(CASE WHEN CAST(stats.y AS DOUBLE PRECISION) IS NOT NULL THEN CAST(stats.x AS DOUBLE PRECISION) END * CASE WHEN CAST(stats.y AS DOUBLE PRECISION) IS NOT NULL THEN CAST(stats.x AS DOUBLE PRECISION) END) - SUM(CASE WHEN CAST(stats.y AS DOUBLE PRECISION) IS NOT NULL THEN CAST(stats.x AS DOUBLE PRECISION) END) * SUM(CASE WHEN CAST(stats.y AS DOUBLE PRECISION) IS NOT NULL THEN CAST(stats.x AS DOUBLE PRECISION) END) / COUNT(CASE WHEN CAST(stats.y AS DOUBLE PRECISION) IS NOT NULL THEN CAST(stats.x AS DOUBLE PRECISION) END)) * (SUM(CASE WHEN CAST(stats.x AS DOUBLE PRECISION) IS NOT NULL THEN CAST(stats.y AS DOUBLE PRECISION) END * CASE WHEN CAST(stats.x AS DOUBLE PRECISION) IS NOT NULL THEN CAST(stats.y AS DOUBLE PRECISION) END) - SUM(CASE WHEN CAST(stats.x AS DOUBLE PRECISION) IS NOT NULL THEN CAST(stats.y AS DOUBLE PRECISION) END) * SUM(CASE WHEN CAST(stats.x AS DOUBLE PRECISION) IS NOT NULL THEN CAST(stats.y AS DOUBLE PRECISION) END) / COUNT(CASE WHEN CAST(stats.x AS DOUBLE PRECISION) IS NOT NULL THEN CAST(stats.y AS DOUBLE PRECISION) END))) AS r,
+   AVG(CASE WHEN CAST(stats.x AS DOUBLE PRECISION) IS NOT NULL THEN CAST(stats.y AS DOUBLE PRECISION) END) - (SUM(CASE WHEN CAST(stats.y AS DOUBLE PRECISION) IS NOT NULL THEN CAST(stats.x AS DOUBLE PRECISION) END * CASE WHEN CAST(stats.x AS DOUBLE PRECISION) IS NOT NULL THEN CAST(stats.y AS DOUBLE PRECISION) END) - SUM(CASE WHEN CAST(stats.y AS DOUBLE PRECISION) IS NOT NULL THEN CAST(stats.x AS DOUBLE PRECISION) END) * SUM(CASE WHEN CAST(stats.x AS DOUBLE PRECISION) IS NOT NULL THEN CAST(stats.y AS DOUBLE PRECISION) END) / COUNT(CASE WHEN CAST(stats.y AS DOUBLE PRECISION) IS NOT NULL THEN CAST(stats.x AS DOUBLE PRECISION) END)) / (SUM(CASE WHEN CAST(stats.y AS DOUBLE PRECISION) IS NOT NULL THEN CAST(stats.x AS DOUBLE PRECISION) END * CASE WHEN CAST(stats.y AS DOUBLE PRECISION) IS NOT NULL THEN CAST(stats.x AS DOUBLE PRECISION) END) - SUM(CASE WHEN CAST(stats.y AS DOUBLE PRECISION) IS NOT NULL THEN CAST(stats.x AS DOUBLE PRECISION) END) * SUM(CASE WHEN CAST(stats.y AS DOUBLE PRECISION) IS NOT NULL THEN CAST(stats.x AS DOUBLE PRECISION) END) / COUNT(CASE WHEN CAST(stats.y AS DOUBLE PRECISION) IS NOT NULL THEN CAST(stats.x AS DOUBLE PRECISION) END)) * AVG(CASE WHEN CAST(stats.y AS DOUBLE PRECISION) IS NOT NULL THEN CAST(stats.x AS DOUBLE PRECISION) END) AS a,
+   (SUM(CASE WHEN CAST(stats.y AS DOUBLE PRECISION) IS NOT NULL THEN CAST(stats.x AS DOUBLE PRECISION) END * CASE WHEN CAST(stats.x AS DOUBLE PRECISION) IS NOT NULL THEN CAST(stats.y AS DOUBLE PRECISION) END) - SUM(CASE WHEN CAST(stats.y AS DOUBLE PRECISION) IS NOT NULL THEN CAST(stats.x AS DOUBLE PRECISION) END) * SUM(CASE WHEN CAST(stats.x AS DOUBLE PRECISION) IS NOT NULL THEN CAST(stats.y AS DOUBLE PRECISION) END) / COUNT(CASE WHEN CAST(stats.y AS DOUBLE PRECISION) IS NOT NULL THEN CAST(stats.x AS DOUBLE PRECISION) END)) / (SUM(CASE WHEN CAST(stats.y AS DOUBLE PRECISION) IS NOT NULL THEN CAST(stats.x AS DOUBLE PRECISION) END * CASE WHEN CAST(stats.y AS DOUBLE PRECISION) IS NOT NULL THEN CAST(stats.x AS DOUBLE PRECISION) END) - SUM(CASE WHEN CAST(stats.y AS DOUBLE PRECISION) IS NOT NULL THEN CAST(stats.x AS DOUBLE PRECISION) END) * SUM(CASE WHEN CAST(stats.y AS DOUBLE PRECISION) IS NOT NULL THEN CAST(stats.x AS DOUBLE PRECISION) END) / COUNT(CASE WHEN CAST(stats.y AS DOUBLE PRECISION) IS NOT NULL THEN CAST(stats.x AS DOUBLE PRECISION) END)) AS b
+FROM stats;